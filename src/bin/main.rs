@@ -3,19 +3,35 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{format_err, Result};
-use commentfmt::{load_config, CliOptions, Config, EmitMode, Verbosity};
+use commentfmt::config::unknown_option_message;
+use commentfmt::format_report::check_formatted_text;
+use commentfmt::ignore_path::IgnorePathSet;
+use commentfmt::{load_config, CliOptions, Config, EmitMode, ErrorKind, Verbosity};
 use getopts::{Matches, Options};
 use io::Error as IoError;
+use move_compiler::diagnostics::Diagnostics;
 use movefmt::{
-    core::fmt::format_entry,
-    tools::movefmt_diff::{make_diff, print_mismatches_default_message, DIFF_CONTEXT_SIZE},
+    core::fmt::{format_entry, format_entry_with_timer},
+    emitter::{
+        parse_errors, CheckstyleEmitter, Emitter, FormattedFile, JsonEmitter, ParseError,
+        PatchEmitter, SarifEmitter,
+    },
+    tools::cache::{FormatCache, CACHE_FILE_NAME},
+    tools::file_lines::{expand_to_items, format_line_ranges, FileLines},
+    tools::movefmt_diff::{
+        diff_context_size, make_diff, print_mismatches_default_message, DIFF_CONTEXT_SIZE,
+    },
+    tools::package::{collect_package_files, find_package_root},
+    tools::report::{ReportFormat, RunReport},
+    tools::source_files::{collect_source_files, SourceFileFilter},
     tools::utils::*,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use thiserror::Error;
 use tracing_subscriber::EnvFilter;
 
@@ -28,8 +44,9 @@ fn main() {
     let exit_code = match execute(&opts) {
         Ok(code) => code,
         Err(e) => {
-            tracing::info!("{e:#}");
-            1
+            eprintln!("Error: {e:#}");
+            // 1 means that some files would be reformatted, see `--check`.
+            EXIT_ERROR
         }
     };
     // Make sure standard output is flushed before we exit.
@@ -46,6 +63,12 @@ fn main() {
 enum Operation {
     /// Format files and their child modules.
     Format { files: Vec<PathBuf> },
+    /// Format source read from stdin and write the result to stdout.
+    Stdin {
+        input: String,
+        /// The path the input is pretended to come from, used to resolve the config.
+        filepath: Option<PathBuf>,
+    },
     /// Print the help message.
     Help(HelpOp),
     /// Print version information
@@ -54,6 +77,9 @@ enum Operation {
     ConfigOutputDefault { path: Option<String> },
     /// Output current config (as if formatting to a file) to stdout
     ConfigOutputCurrent { path: Option<String> },
+    /// Output the options of the current directory's config that differ from
+    /// their defaults to a file, or stdout if None
+    ConfigOutputMinimal { path: Option<String> },
 }
 
 /// movefmt operations errors.
@@ -65,6 +91,15 @@ pub enum OperationError {
     /// An unknown print-config option was requested.
     #[error("Unknown print-config option: `{0}`.")]
     UnknownPrintConfigTopic(String),
+    /// Stdin was requested together with file arguments.
+    #[error("Standard input can't be formatted together with file arguments.")]
+    StdinWithFiles,
+    /// An `--include`, `--exclude` or file argument glob could not be parsed.
+    #[error("{0}")]
+    InvalidGlobPattern(ErrorKind),
+    /// The manifest of a Move package could not be read.
+    #[error("Failed to load the Move package `{0}`: {1:#}")]
+    InvalidPackage(String, anyhow::Error),
     /// An io error during reading or writing.
     #[error("{0}")]
     IoError(IoError),
@@ -76,6 +111,34 @@ impl From<IoError> for OperationError {
     }
 }
 
+/// The outcome of formatting a single file, used to compute the exit code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileStatus {
+    /// The file is already formatted.
+    Unchanged,
+    /// Formatting the file produced a different output.
+    Changed,
+    /// The file could not be read or parsed.
+    Failed,
+}
+
+impl FileStatus {
+    fn summary_tag(&self) -> &'static str {
+        match self {
+            FileStatus::Unchanged => "ok",
+            FileStatus::Changed => "would reformat",
+            FileStatus::Failed => "error",
+        }
+    }
+}
+
+/// Exit code when every file is formatted.
+const EXIT_OK: i32 = 0;
+/// Exit code when at least one file would be changed by formatting.
+const EXIT_DIFF: i32 = 1;
+/// Exit code when at least one file could not be read or parsed.
+const EXIT_ERROR: i32 = 2;
+
 /// Arguments to `--help`
 enum HelpOp {
     None,
@@ -84,9 +147,98 @@ enum HelpOp {
 
 fn make_opts() -> Options {
     let mut opts = Options::new();
-    let emit_opts = "[files|new_files|stdout|check_diff]";
+    let emit_opts = "[files|new_files|stdout|check_diff|json|checkstyle|sarif|patch]";
 
+    opts.optflag(
+        "",
+        "check",
+        "Run in 'check' mode. Exits with 0 if input is formatted correctly. Exits \
+         with 1 and prints a diff if formatting is required. Exits with 2 if any \
+         file could not be read or parsed.",
+    );
     opts.optopt("", "emit", "What data to emit and how", emit_opts);
+    opts.optflag(
+        "",
+        "backup",
+        "Keep the original of every file rewritten in place as <file>.bak",
+    );
+    opts.optflag(
+        "l",
+        "files-with-diff",
+        "Prints the names of files whose formatting differs, without writing them or \
+         printing diffs. Exits with 1 if any file would be reformatted",
+    );
+    opts.optmulti(
+        "",
+        "include",
+        "Only format files under directory or glob arguments matching this glob",
+        "[GLOB]",
+    );
+    opts.optmulti(
+        "",
+        "exclude",
+        "Skip files under directory or glob arguments matching this glob",
+        "[GLOB]",
+    );
+    opts.optflag(
+        "",
+        "package",
+        "Treat each argument as a Move package directory and format the sources, tests, \
+         scripts and examples listed by its Move.toml. This is the default when the \
+         current directory contains a Move.toml and no argument is given",
+    );
+    opts.optflag(
+        "",
+        "include-deps",
+        "In package mode, also format the local (path) dependencies of the packages",
+    );
+    opts.optflag(
+        "",
+        "stdin",
+        "Read source from stdin and write the formatted result to stdout (same as `-`)",
+    );
+    opts.optopt(
+        "",
+        "stdin-filepath",
+        "Resolve movefmt.toml as if the stdin input was read from this path",
+        "[Path of the file being formatted]",
+    );
+    opts.optopt(
+        "",
+        "file-lines",
+        "Format only these line ranges, leaving the rest of the files untouched. \
+         Without file arguments, the files listed here are formatted",
+        "[{\"file\":\"a.move\",\"range\":[10,40]}]",
+    );
+    opts.optopt(
+        "",
+        "changed-since",
+        "Format only the functions, structs and specs changed in the working tree since \
+         this git revision. Without file arguments, the changed files are formatted",
+        "[REV]",
+    );
+    opts.optflagopt(
+        "",
+        "cache",
+        "Skip the files recorded as formatted in this cache file, and record the files \
+         found formatted. Defaults to .movefmt-cache in the current directory",
+        "[PATH]",
+    );
+    opts.optopt(
+        "",
+        "report",
+        "Print a summary of the run to stderr: the number of files changed, unchanged and \
+         skipped, the lines changed, the total time and the slowest files",
+        "[text|json]",
+    );
+    #[cfg(all(feature = "pprof", not(target_os = "windows")))]
+    opts.optopt(
+        "",
+        "profile",
+        "Sample the CPU during the whole run and write a flamegraph (.svg) or a pprof \
+         protobuf profile (.pb) to this file",
+        "[out.svg|out.pb]",
+    );
     opts.optopt(
         "",
         "config-path",
@@ -96,8 +248,9 @@ fn make_opts() -> Options {
     opts.optopt(
         "",
         "print-config",
-        "Dumps a default or current config to PATH(eg: movefmt.toml)",
-        "[default|current] PATH",
+        "Dumps a default, minimal or current config to PATH(eg: movefmt.toml). \
+         A minimal config only has the options that differ from their defaults",
+        "[default|minimal|current] PATH",
     );
     opts.optmulti(
         "",
@@ -106,6 +259,12 @@ fn make_opts() -> Options {
         "[key1=val1,key2=val2...]",
     );
 
+    opts.optopt(
+        "j",
+        "jobs",
+        "Number of files to format in parallel, defaults to the number of CPUs",
+        "N",
+    );
     opts.optflag("v", "verbose", "Print verbose output");
     opts.optflag("q", "quiet", "Print less output");
     opts.optflag("V", "version", "Show version information");
@@ -119,14 +278,35 @@ fn make_opts() -> Options {
 fn execute(opts: &Options) -> Result<i32> {
     let matches = opts.parse(env::args().skip(1))?;
     let options = GetOptsOptions::from_matches(&matches)?;
+    #[cfg(all(feature = "pprof", not(target_os = "windows")))]
+    let profiler = match matches.opt_str("profile") {
+        Some(output) => Some(CpuProfiler::start(PathBuf::from(output))?),
+        None => None,
+    };
+
+    // Every `?` is within `run_operation`, so the profile is written whatever
+    // the run returns.
+    let result = determine_operation(&matches, &options)
+        .map_err(anyhow::Error::from)
+        .and_then(|operation| run_operation(opts, operation, options));
+
+    #[cfg(all(feature = "pprof", not(target_os = "windows")))]
+    if let Some(profiler) = profiler {
+        let finished = profiler.finish();
+        return result.and_then(|exit_code| finished.map(|()| exit_code));
+    }
+    result
+}
 
-    match determine_operation(&matches)? {
+/// Runs `operation`, returning the exit code.
+fn run_operation(opts: &Options, operation: Operation, options: GetOptsOptions) -> Result<i32> {
+    match operation {
         Operation::Help(HelpOp::None) => {
             print_usage_to_stdout(opts, "");
             Ok(0)
         }
         Operation::Help(HelpOp::Config) => {
-            print_usage_to_stdout(opts, "");
+            Config::print_docs(&mut io::stdout())?;
             Ok(0)
         }
         Operation::Version => {
@@ -158,16 +338,87 @@ fn execute(opts: &Options) -> Result<i32> {
 
             Ok(0)
         }
+        Operation::ConfigOutputMinimal { path } => {
+            let (config, _) = load_config(Some(&env::current_dir()?), Some(options))?;
+            let toml = config.minimal_options().to_toml()?;
+            if let Some(path) = path {
+                let mut file = File::create(path)?;
+                file.write_all(toml.as_bytes())?;
+            } else {
+                io::stdout().write_all(toml.as_bytes())?;
+            }
+            Ok(0)
+        }
         Operation::Format { files } => format(files, &options),
+        Operation::Stdin { input, filepath } => format_stdin(input, filepath, &options),
     }
 }
 
+/// A file to format, together with the config resolved for it.
+struct FormatJob {
+    file: PathBuf,
+    config: Config,
+}
+
+/// What formatting a single file produced.
+struct FormatOutcome {
+    content_origin: String,
+    /// The key of the file in the `--cache`, if one is used.
+    cache_key: Option<String>,
+    /// Whether the cache says the file is already formatted, so it wasn't formatted again.
+    cached: bool,
+    /// The formatted text, or the rendered and the located parse diagnostics.
+    result: Result<(String, Timer), (Vec<u8>, Vec<ParseError>)>,
+}
+
+/// Loads the config like `load_config`, and fails if it requires another version
+/// of movefmt.
+fn load_checked_config(
+    dir: Option<&Path>,
+    options: &GetOptsOptions,
+) -> Result<(Config, Option<PathBuf>)> {
+    let (config, config_path) = load_config(dir, Some(options.clone()))?;
+    if let Err(err) = config.version_meets_requirement(env!("CARGO_PKG_VERSION")) {
+        return Err(match &config_path {
+            Some(path) => format_err!("{}: {}", path.display(), err),
+            None => err.into(),
+        });
+    }
+    Ok((config, config_path))
+}
+
+/// Loads the config of `dir` like `load_checked_config`, together with the
+/// matcher of its `ignore` option, which only depends on the directory.
+fn load_dir_config(
+    dir: Option<&Path>,
+    options: &GetOptsOptions,
+) -> Result<(Config, Option<PathBuf>, IgnorePathSet)> {
+    let (config, config_path) = load_checked_config(dir, options)?;
+    let ignore_set =
+        IgnorePathSet::from_ignore_list(&config.ignore()).map_err(ErrorKind::InvalidGlobPattern)?;
+    Ok((config, config_path, ignore_set))
+}
+
+/// Returns the config for `file`, with the `[[overrides]]` sections matching it
+/// applied. The command line options still take priority over them.
+fn config_for_file(config: &Config, file: &Path, options: &GetOptsOptions) -> Result<Config> {
+    let mut config = config.apply_path_overrides(file)?;
+    options.clone().apply_to(&mut config);
+    Ok(config)
+}
+
 fn format(files: Vec<PathBuf>, options: &GetOptsOptions) -> Result<i32> {
     eprintln!("options = {:?}", options);
-    let (config, config_path) = load_config(None, Some(options.clone()))?;
-    let mut use_config = config.clone();
+    let start = Instant::now();
+    let mut report = RunReport {
+        files: files.len(),
+        ..Default::default()
+    };
+    let (config, config_path, ignore_set) = load_dir_config(None, options)?;
     let mut success_cnt = 0;
     let mut skips_cnt = 0;
+    let mut check_diff = false;
+    let mut statuses: Vec<(PathBuf, FileStatus)> = vec![];
     tracing::info!(
         "config.[verbose, indent] = [{:?}, {:?}], {:?}",
         config.verbose(),
@@ -181,98 +432,486 @@ fn format(files: Vec<PathBuf>, options: &GetOptsOptions) -> Result<i32> {
         }
     }
 
+    // Resolving a config reads the file system, so it's done once per directory.
+    // A directory whose config can't be loaded fails its files, not the whole run.
+    let mut dir_configs: HashMap<PathBuf, Result<(Config, Option<PathBuf>, IgnorePathSet)>> =
+        HashMap::new();
+    let mut jobs = vec![];
     for file in files {
         if !file.exists() {
-            eprintln!("Error: file `{}` does not exist", file.to_str().unwrap());
+            eprintln!("Error: file `{}` does not exist", file.display());
+            report.skipped += 1;
+            statuses.push((file, FileStatus::Failed));
             continue;
         } else if file.is_dir() {
-            eprintln!("Error: `{}` is a directory", file.to_str().unwrap());
+            eprintln!("Error: `{}` is a directory", file.display());
+            report.skipped += 1;
+            statuses.push((file, FileStatus::Failed));
             continue;
-        } else {
-            // Check the file directory if the config-path could not be read or not provided
-            if config_path.is_none() {
-                let (local_config, config_path) =
-                    load_config(Some(file.parent().unwrap()), Some(options.clone()))?;
-                tracing::debug!("local config_path = {:?}", config_path);
-                if local_config.verbose() == Verbosity::Verbose {
-                    if let Some(path) = config_path {
-                        println!(
-                            "Using movefmt local config file {} for {}",
-                            path.display(),
-                            file.display()
-                        );
-                        use_config = local_config.clone();
-                    }
+        }
+
+        // Check the file directory if the config-path could not be read or not provided
+        let (use_config, use_ignore_set) = if config_path.is_none() {
+            let dir = file.parent().unwrap().to_path_buf();
+            let local = dir_configs.entry(dir).or_insert_with_key(|dir| {
+                let local = load_dir_config(Some(dir.as_path()), options);
+                match &local {
+                    Ok((_, path, _)) => tracing::debug!("local config_path = {:?}", path),
+                    Err(e) => eprintln!("Error: {e:#}"),
+                }
+                local
+            });
+            let (local_config, local_config_path, local_ignore_set) = match local {
+                Ok(local) => &*local,
+                Err(_) => {
+                    report.skipped += 1;
+                    statuses.push((file, FileStatus::Failed));
+                    continue;
                 }
-            } else if use_config.verbose() == Verbosity::Verbose {
+            };
+            if local_config.verbose() == Verbosity::Verbose {
+                if let Some(path) = local_config_path {
+                    println!(
+                        "Using movefmt local config file {} for {}",
+                        path.display(),
+                        file.display()
+                    );
+                }
+            }
+            (local_config, local_ignore_set)
+        } else {
+            if config.verbose() == Verbosity::Verbose {
                 println!(
                     "Using movefmt config file {} for {}",
                     config_path.clone().unwrap_or_default().display(),
                     file.display()
                 );
             }
+            (&config, &ignore_set)
+        };
+        let use_config = match config_for_file(use_config, &file, options) {
+            Ok(use_config) => use_config,
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                report.skipped += 1;
+                statuses.push((file, FileStatus::Failed));
+                continue;
+            }
+        };
+
+        if use_ignore_set.is_match(&file) {
+            if use_config.verbose() == Verbosity::Verbose {
+                println!("Skipping {} because of the ignore option", file.display());
+            }
+            report.ignored += 1;
+            continue;
         }
+        jobs.push(FormatJob {
+            file,
+            config: use_config,
+        });
+    }
+
+    let mut cache = options.cache_path.as_deref().map(FormatCache::load);
+    let outcomes = parallel_map(
+        jobs.iter()
+            .map(|job| (job.file.clone(), job.config.clone()))
+            .collect(),
+        options.jobs,
+        |(file, config)| format_file(&file, config, cache.as_ref()),
+    );
 
-        let content_origin = std::fs::read_to_string(file.as_path()).unwrap();
-        if use_config.verbose() == Verbosity::Verbose {
+    // Machine readable output is a single document for the whole run, so its
+    // mode comes from `--emit` only, never from the config of a directory.
+    let mut emitter = match options.emit_mode.filter(|mode| mode.is_machine_readable()) {
+        Some(emit_mode) => Some(create_emitter(emit_mode)?),
+        None => None,
+    };
+    // Everything is reported in the order of the files, whichever thread formatted them.
+    for (FormatJob { file, config }, outcome) in jobs.into_iter().zip(outcomes) {
+        let FormatOutcome {
+            content_origin,
+            cache_key,
+            cached,
+            result,
+        } = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                // A file that can't be read doesn't stop the others.
+                eprintln!("Error: failed to read `{}`: {}", file.display(), e);
+                skips_cnt += 1;
+                statuses.push((file, FileStatus::Failed));
+                continue;
+            }
+        };
+        if config.verbose() == Verbosity::Verbose {
             println!("Formatting {}", file.display());
         }
-        match format_entry(content_origin.clone(), use_config.clone()) {
-            Ok(formatted_text) => {
+        // A config can't select machine readable output, see `emitter` above.
+        let emit_mode = options.emit_mode.unwrap_or_else(|| config.emit_mode());
+        match result {
+            Ok((formatted_text, timer)) => {
+                if let (Some(cache), Some(key)) = (cache.as_mut(), cache_key) {
+                    if formatted_text == content_origin {
+                        cache.insert(file.clone(), key);
+                    }
+                }
+                let formatted_text =
+                    restrict_changes(options, &file, &content_origin, formatted_text);
+                if cached {
+                    if config.verbose() == Verbosity::Verbose {
+                        println!(
+                            "Skipping {} because it is cached as formatted",
+                            file.display()
+                        );
+                    }
+                } else if config.verbose() == Verbosity::Verbose {
+                    println!("{}", timer.phase_times());
+                }
                 success_cnt += 1;
-                let emit_mode = if let Some(op_emit) = options.emit_mode {
-                    op_emit
+                let mut status = if formatted_text == content_origin {
+                    FileStatus::Unchanged
                 } else {
-                    use_config.emit_mode()
+                    FileStatus::Changed
                 };
+                report.add_formatted_file(&file, &content_origin, &formatted_text, &timer);
+                let formatting_failed =
+                    report_formatting_errors(&file.display().to_string(), &formatted_text, &config);
                 match emit_mode {
                     EmitMode::NewFiles => {
-                        std::fs::write(mk_result_filepath(&file.to_path_buf()), formatted_text)?
+                        let new_file = mk_result_filepath(&file.to_path_buf());
+                        if let Err(e) = std::fs::write(&new_file, formatted_text) {
+                            eprintln!("Error: failed to write `{}`: {}", new_file.display(), e);
+                            status = FileStatus::Failed;
+                        }
                     }
                     EmitMode::Files => {
-                        std::fs::write(&file, formatted_text)?;
+                        if status == FileStatus::Changed {
+                            if let Err(e) = replace_file(
+                                &file,
+                                &content_origin,
+                                &formatted_text,
+                                options.backup,
+                            ) {
+                                eprintln!("Error: failed to write `{}`: {}", file.display(), e);
+                                status = FileStatus::Failed;
+                            }
+                        }
                     }
                     EmitMode::Stdout => {
                         println!("{}", formatted_text);
                     }
                     EmitMode::Diff => {
-                        let compare =
-                            make_diff(&formatted_text, &content_origin, DIFF_CONTEXT_SIZE);
-                        if !compare.is_empty() {
-                            let mut failures = HashMap::new();
-                            failures.insert(file.to_owned(), compare);
-                            print_mismatches_default_message(failures);
+                        check_diff = true;
+                        if options.files_with_diff {
+                            if status == FileStatus::Changed {
+                                println!("{}", file.display());
+                            }
+                        } else {
+                            let compare =
+                                make_diff(&content_origin, &formatted_text, DIFF_CONTEXT_SIZE);
+                            if !compare.is_empty() {
+                                let mut failures = HashMap::new();
+                                failures.insert(file.to_owned(), compare);
+                                print_mismatches_default_message(failures);
+                            }
                         }
                     }
+                    EmitMode::Json | EmitMode::Checkstyle | EmitMode::Sarif | EmitMode::Patch => {
+                        if let Some(emitter) = emitter.as_mut() {
+                            emitter.emit_formatted_file(
+                                &mut io::stdout(),
+                                FormattedFile {
+                                    filename: &file,
+                                    original_text: &content_origin,
+                                    formatted_text: &formatted_text,
+                                },
+                            )?;
+                        }
+                    }
+                }
+                if formatting_failed {
+                    status = FileStatus::Failed;
                 }
+                statuses.push((file, status));
             }
-            Err(diags) => {
+            Err((diags_buf, errors)) => {
                 skips_cnt += 1;
-                let mut files_source_text: move_compiler::diagnostics::FilesSourceText =
-                    HashMap::new();
-                files_source_text.insert(
-                    move_command_line_common::files::FileHash::empty(),
-                    (file.display().to_string().into(), content_origin.clone()),
-                );
-                let diags_buf = move_compiler::diagnostics::report_diagnostics_to_color_buffer(
-                    &files_source_text,
-                    diags,
-                );
-                if std::io::stdout().write_all(&diags_buf).is_err() {
+                if let Some(emitter) = emitter.as_mut() {
+                    emitter.emit_parse_errors(&mut io::stdout(), &file, &errors)?;
+                    // Keep stdout for the report.
+                    io::stderr().write_all(&diags_buf)?;
+                } else if options.check || options.files_with_diff {
+                    // Keep stdout for the diff or the list of files to reformat.
+                    io::stderr().write_all(&diags_buf)?;
+                } else if std::io::stdout().write_all(&diags_buf).is_err() {
                     // Cannot output compiler diagnostics;
                     // https://github.com/movebit/movefmt/issues/2
                     eprintln!("file '{:?}' skipped because of parse not ok", file);
                 }
+                statuses.push((file, FileStatus::Failed));
             }
         }
     }
+    if let Some(emitter) = &emitter {
+        emitter.emit_footer(&mut io::stdout())?;
+    }
+    if let (Some(cache), Some(path)) = (cache, options.cache_path.as_ref()) {
+        if let Err(e) = cache.save(path) {
+            eprintln!(
+                "Warning: failed to write the cache {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
     if skips_cnt > 0 {
-        eprintln!("{:?} files skipped because of parse failed", skips_cnt);
+        eprintln!(
+            "{:?} files skipped because of read or parse failed",
+            skips_cnt
+        );
     }
-    if success_cnt > 0 {
+    // Don't mix anything into machine readable output.
+    if success_cnt > 0 && !options.check && !options.files_with_diff && emitter.is_none() {
         println!("{:?} files successfully formatted", success_cnt);
     }
-    Ok(0)
+    if options.check && !options.files_with_diff {
+        print_check_summary(&statuses);
+    }
+    if let Some(report_format) = options.report {
+        report.skipped += skips_cnt;
+        report.finish(start.elapsed());
+        // Keep stdout for the formatted output.
+        eprintln!("{}", report.render(report_format)?.trim_end());
+    }
+
+    Ok(exit_code(&statuses, check_diff))
+}
+
+/// Creates the emitter for a machine readable `emit_mode` and writes its header.
+fn create_emitter(emit_mode: EmitMode) -> Result<Box<dyn Emitter>> {
+    let emitter: Box<dyn Emitter> = match emit_mode {
+        EmitMode::Json => Box::<JsonEmitter>::default(),
+        EmitMode::Checkstyle => Box::<CheckstyleEmitter>::default(),
+        EmitMode::Sarif => Box::<SarifEmitter>::default(),
+        EmitMode::Patch => Box::new(PatchEmitter::new(diff_context_size(), env::current_dir()?)),
+        _ => {
+            return Err(format_err!(
+                "`{}` is not a machine readable emit mode",
+                emit_mode
+            ))
+        }
+    };
+    emitter.emit_header(&mut io::stdout())?;
+    Ok(emitter)
+}
+
+/// Reads and formats a single file. This runs on the worker threads, so it
+/// must not print anything itself.
+fn format_file(
+    file: &Path,
+    config: Config,
+    cache: Option<&FormatCache>,
+) -> Result<FormatOutcome, OperationError> {
+    let content_origin = String::from_utf8(std::fs::read(file)?).map_err(|e| {
+        OperationError::IoError(IoError::new(io::ErrorKind::InvalidData, e.utf8_error()))
+    })?;
+    let cache_key = cache.map(|_| FormatCache::key(&content_origin, &config));
+    if let (Some(cache), Some(key)) = (cache, cache_key.as_ref()) {
+        if cache.is_formatted(file, key) {
+            let timer = Timer::start().done_parsing().done_formatting();
+            return Ok(FormatOutcome {
+                result: Ok((content_origin.clone(), timer)),
+                content_origin,
+                cache_key,
+                cached: true,
+            });
+        }
+    }
+    let (bom, content) = split_bom(&content_origin);
+    let result = format_entry_with_timer(content, config)
+        .map(|(formatted_text, timer)| (format!("{}{}", bom, formatted_text), timer))
+        .map_err(|diags| {
+            (
+                diagnostics_buffer(file.display().to_string(), content, diags.clone()),
+                parse_errors(content, diags),
+            )
+        });
+    Ok(FormatOutcome {
+        content_origin,
+        cache_key,
+        cached: false,
+        result,
+    })
+}
+
+/// Prints the lines of `formatted_text` that are too wide or end with
+/// whitespace as `file:line:column` warnings, or as errors when the config
+/// asks to fail on them. Returns `true` if there is any error.
+fn report_formatting_errors(file_name: &str, formatted_text: &str, config: &Config) -> bool {
+    let mut failed = false;
+    for error in check_formatted_text(formatted_text, config) {
+        let is_error = error.is_error(config);
+        failed |= is_error;
+        if is_error || config.verbose() != Verbosity::Quiet {
+            eprintln!(
+                "{}:{}:{}: {}: {}",
+                file_name,
+                error.line,
+                error.column,
+                if is_error { "error" } else { "warning" },
+                error.kind
+            );
+        }
+    }
+    failed
+}
+
+/// Splits the UTF-8 byte order mark, if any, from `content`. The mark is not
+/// Move source, so it's kept aside while formatting and written back as is.
+fn split_bom(content: &str) -> (&str, &str) {
+    match content.strip_prefix('\u{feff}') {
+        Some(rest) => ("\u{feff}", rest),
+        None => ("", content),
+    }
+}
+
+/// Keeps only the changes within the `--file-lines` ranges, or within the
+/// items changed since the `--changed-since` revision.
+fn restrict_changes(
+    options: &GetOptsOptions,
+    file: &Path,
+    original: &str,
+    formatted: String,
+) -> String {
+    if let Some(file_lines) = &options.file_lines {
+        format_line_ranges(original, &formatted, file_lines.ranges_of(file))
+    } else if let Some(changed_lines) = &options.changed_lines {
+        // The byte order mark is not Move source. It doesn't span a line, so the
+        // line numbers of the items are the same with or without it.
+        let (_, content) = split_bom(original);
+        let items = expand_to_items(content, changed_lines.ranges_of(file));
+        format_line_ranges(original, &formatted, &items)
+    } else {
+        formatted
+    }
+}
+
+fn format_stdin(input: String, filepath: Option<PathBuf>, options: &GetOptsOptions) -> Result<i32> {
+    // Resolve the config the same way as for a real file living at `filepath`.
+    let config_dir = filepath.as_ref().and_then(|p| {
+        let p = p.canonicalize().unwrap_or(p.clone());
+        p.parent().map(Path::to_path_buf)
+    });
+    let (config, config_path) = load_checked_config(config_dir.as_deref(), options)?;
+    let config = match &filepath {
+        Some(filepath) => config_for_file(&config, filepath, options)?,
+        None => config,
+    };
+    if config.verbose() == Verbosity::Verbose {
+        if let Some(path) = config_path.as_ref() {
+            eprintln!("Using movefmt config file {} for stdin", path.display());
+        }
+    }
+
+    let file_name = filepath
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "<stdin>".to_string());
+    let (bom, content) = split_bom(&input);
+    match format_entry(content, config.clone()) {
+        Ok(formatted_text) => {
+            let formatted_text = format!("{}{}", bom, formatted_text);
+            let formatted_text = match &filepath {
+                Some(filepath) => restrict_changes(options, filepath, &input, formatted_text),
+                None => formatted_text,
+            };
+            let formatting_failed = report_formatting_errors(&file_name, &formatted_text, &config);
+            let exit_code = if let Some(emit_mode) =
+                options.emit_mode.filter(|mode| mode.is_machine_readable())
+            {
+                let mut emitter = create_emitter(emit_mode)?;
+                emitter.emit_formatted_file(
+                    &mut io::stdout(),
+                    FormattedFile {
+                        filename: Path::new(&file_name),
+                        original_text: &input,
+                        formatted_text: &formatted_text,
+                    },
+                )?;
+                emitter.emit_footer(&mut io::stdout())?;
+                EXIT_OK
+            } else if options.emit_mode == Some(EmitMode::Diff) {
+                let compare = make_diff(&input, &formatted_text, DIFF_CONTEXT_SIZE);
+                if compare.is_empty() {
+                    EXIT_OK
+                } else if options.files_with_diff {
+                    println!("{}", file_name);
+                    EXIT_DIFF
+                } else {
+                    let mut failures = HashMap::new();
+                    failures.insert(PathBuf::from(&file_name), compare);
+                    print_mismatches_default_message(failures);
+                    EXIT_DIFF
+                }
+            } else {
+                io::stdout().write_all(formatted_text.as_bytes())?;
+                EXIT_OK
+            };
+            Ok(if formatting_failed {
+                EXIT_ERROR
+            } else {
+                exit_code
+            })
+        }
+        Err(diags) => {
+            if let Some(emit_mode) = options.emit_mode.filter(|mode| mode.is_machine_readable()) {
+                let mut emitter = create_emitter(emit_mode)?;
+                let errors = parse_errors(content, diags.clone());
+                emitter.emit_parse_errors(&mut io::stdout(), Path::new(&file_name), &errors)?;
+                emitter.emit_footer(&mut io::stdout())?;
+            }
+            let diags_buf = diagnostics_buffer(file_name, content, diags);
+            io::stderr().write_all(&diags_buf)?;
+            Ok(EXIT_ERROR)
+        }
+    }
+}
+
+/// Renders parse diagnostics of a single source as colored text.
+fn diagnostics_buffer(file_name: String, content: &str, diags: Diagnostics) -> Vec<u8> {
+    let mut files_source_text: move_compiler::diagnostics::FilesSourceText = HashMap::new();
+    files_source_text.insert(
+        move_command_line_common::files::FileHash::empty(),
+        (file_name.into(), content.to_string()),
+    );
+    move_compiler::diagnostics::report_diagnostics_to_color_buffer(&files_source_text, diags)
+}
+
+/// Prints one line per checked file, followed by the totals.
+fn print_check_summary(statuses: &[(PathBuf, FileStatus)]) {
+    let count = |status| statuses.iter().filter(|(_, s)| *s == status).count();
+    for (file, status) in statuses {
+        println!("{:>14}: {}", status.summary_tag(), file.display());
+    }
+    println!(
+        "{} files checked: {} formatted, {} would be reformatted, {} failed",
+        statuses.len(),
+        count(FileStatus::Unchanged),
+        count(FileStatus::Changed),
+        count(FileStatus::Failed),
+    );
+}
+
+/// Errors take priority over diffs, which are only reported when checking.
+fn exit_code(statuses: &[(PathBuf, FileStatus)], check_diff: bool) -> i32 {
+    if statuses.iter().any(|(_, s)| *s == FileStatus::Failed) {
+        EXIT_ERROR
+    } else if check_diff && statuses.iter().any(|(_, s)| *s == FileStatus::Changed) {
+        EXIT_DIFF
+    } else {
+        EXIT_OK
+    }
 }
 
 fn print_usage_to_stdout(opts: &Options, reason: &str) {
@@ -281,7 +920,9 @@ fn print_usage_to_stdout(opts: &Options, reason: &str) {
     } else {
         format!("{reason}\n\n")
     };
-    let msg = format!("{sep}Format Move code\n\nusage: movefmt [options] <file>...");
+    let msg = format!(
+        "{sep}Format Move code\n\nusage: movefmt [options] <file|dir|glob>...\n       movefmt [options] - < <file>"
+    );
     println!("{}", opts.usage(&msg));
 }
 
@@ -289,7 +930,10 @@ fn print_version() {
     println!("movefmt v1.0.2");
 }
 
-fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
+fn determine_operation(
+    matches: &Matches,
+    options: &GetOptsOptions,
+) -> Result<Operation, OperationError> {
     if matches.opt_present("h") {
         let topic = matches.opt_str("h");
         if topic.is_none() {
@@ -304,6 +948,7 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
         match kind.as_str() {
             "default" => return Ok(Operation::ConfigOutputDefault { path }),
             "current" => return Ok(Operation::ConfigOutputCurrent { path }),
+            "minimal" => return Ok(Operation::ConfigOutputMinimal { path }),
             _ => {
                 return Err(OperationError::UnknownPrintConfigTopic(kind));
             }
@@ -314,42 +959,84 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
         return Ok(Operation::Version);
     }
 
-    let mut files: Vec<_> = free_matches
-        .map(|s| {
-            let p = PathBuf::from(s);
-            // we will do comparison later, so here tries to canonicalize first
-            // to get the expected behavior.
-            p.canonicalize().unwrap_or(p)
-        })
-        .collect();
+    let stdin_filepath = matches.opt_str("stdin-filepath").map(PathBuf::from);
+    if matches.opt_present("stdin")
+        || stdin_filepath.is_some()
+        || matches.free.iter().any(|s| s == "-")
+    {
+        if matches.free.iter().any(|s| s != "-") {
+            return Err(OperationError::StdinWithFiles);
+        }
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(Operation::Stdin {
+            input,
+            filepath: stdin_filepath,
+        });
+    }
 
-    if files.is_empty() {
+    let current_dir = match std::env::current_dir() {
+        Ok(current_dir) => current_dir,
+        Err(_) => {
+            eprintln!("Failed to get the current directory.");
+            return Ok(Operation::Help(HelpOp::None));
+        }
+    };
+    let mut args: Vec<String> = free_matches.cloned().collect();
+    let file_lines = options
+        .file_lines
+        .as_ref()
+        .or(options.changed_lines.as_ref());
+    if let Some(file_lines) = file_lines.filter(|_| args.is_empty()) {
+        return Ok(Operation::Format {
+            files: file_lines.files(),
+        });
+    }
+    let filter = SourceFileFilter::new(
+        &current_dir,
+        &matches.opt_strs("include"),
+        &matches.opt_strs("exclude"),
+    )
+    .map_err(OperationError::InvalidGlobPattern)?;
+
+    // The current directory may be anywhere within a package, e.g. in `sources`.
+    let package_root = if args.is_empty() {
+        find_package_root(&current_dir)
+    } else {
+        None
+    };
+    if matches.opt_present("package") || package_root.is_some() {
+        if args.is_empty() {
+            eprintln!(
+                "no file argument is supplied, movefmt formats the Move package of the current directory......"
+            );
+            let root = package_root.as_ref().unwrap_or(&current_dir);
+            args.push(root.display().to_string());
+        }
+        let mut seen = HashSet::new();
+        let mut files = vec![];
+        for root in args {
+            let package_files =
+                collect_package_files(Path::new(&root), matches.opt_present("include-deps"))
+                    .map_err(|e| OperationError::InvalidPackage(root, e))?;
+            files.extend(
+                package_files
+                    .into_iter()
+                    .filter(|p| filter.is_selected(p) && seen.insert(p.clone())),
+            );
+        }
+        return Ok(Operation::Format { files });
+    }
+
+    if args.is_empty() {
         eprintln!("no file argument is supplied, movefmt runs on current directory by default, \nformatting all .move files within it......");
         eprintln!(
             "\n----------------------------------------------------------------------------\n"
         );
-        if let Ok(current_dir) = std::env::current_dir() {
-            println!("Current directory: {:?}", current_dir.display());
-            for x in walkdir::WalkDir::new(current_dir) {
-                let x = match x {
-                    Ok(x) => x,
-                    Err(_) => {
-                        break;
-                    }
-                };
-                if x.file_type().is_file()
-                    && x.file_name().to_str().unwrap().ends_with(".move")
-                    && !x.file_name().to_str().unwrap().contains(".fmt")
-                    && !x.file_name().to_str().unwrap().contains(".out")
-                {
-                    files.push(x.clone().into_path());
-                }
-            }
-        } else {
-            eprintln!("Failed to get the current directory.");
-            return Ok(Operation::Help(HelpOp::None));
-        }
+        println!("Current directory: {:?}", current_dir.display());
+        args.push(current_dir.display().to_string());
     }
+    let files = collect_source_files(&args, &filter).map_err(OperationError::InvalidGlobPattern)?;
 
     Ok(Operation::Format { files })
 }
@@ -359,6 +1046,14 @@ fn determine_operation(matches: &Matches) -> Result<Operation, OperationError> {
 struct GetOptsOptions {
     quiet: bool,
     verbose: bool,
+    check: bool,
+    files_with_diff: bool,
+    jobs: usize,
+    file_lines: Option<FileLines>,
+    changed_lines: Option<FileLines>,
+    cache_path: Option<PathBuf>,
+    backup: bool,
+    report: Option<ReportFormat>,
     config_path: Option<PathBuf>,
     emit_mode: Option<EmitMode>,
     inline_config: HashMap<String, String>,
@@ -369,14 +1064,62 @@ impl GetOptsOptions {
         let mut options = GetOptsOptions {
             verbose: matches.opt_present("verbose"),
             quiet: matches.opt_present("quiet"),
+            check: matches.opt_present("check"),
+            files_with_diff: matches.opt_present("files-with-diff"),
+            backup: matches.opt_present("backup"),
             ..Default::default()
         };
         if options.verbose && options.quiet {
             return Err(format_err!("Can't use both `--verbose` and `--quiet`"));
         }
+        if options.check && matches.opt_present("emit") {
+            return Err(format_err!("Can't use both `--check` and `--emit`"));
+        }
+        if options.files_with_diff && matches.opt_present("emit") {
+            return Err(format_err!(
+                "Can't use both `--files-with-diff` and `--emit`"
+            ));
+        }
 
+        options.jobs = match matches.opt_str("jobs") {
+            Some(jobs) => match jobs.parse::<usize>() {
+                Ok(jobs) if jobs > 0 => jobs,
+                _ => {
+                    return Err(format_err!(
+                        "`--jobs` expects a positive number, found `{}`",
+                        jobs
+                    ))
+                }
+            },
+            None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        if let Some(json) = matches.opt_str("file-lines") {
+            options.file_lines = Some(FileLines::from_json(&json, &env::current_dir()?)?);
+        }
+        if let Some(rev) = matches.opt_str("changed-since") {
+            if options.file_lines.is_some() {
+                return Err(format_err!(
+                    "Can't use both `--file-lines` and `--changed-since`"
+                ));
+            }
+            options.changed_lines = Some(FileLines::from_git_diff(&rev, &env::current_dir()?)?);
+        }
+        if matches.opt_present("cache") {
+            options.cache_path = Some(
+                matches
+                    .opt_str("cache")
+                    .map_or_else(|| PathBuf::from(CACHE_FILE_NAME), PathBuf::from),
+            );
+        }
+        options.report = matches
+            .opt_str("report")
+            .map(|report| report.parse::<ReportFormat>())
+            .transpose()
+            .map_err(|e| format_err!(e))?;
         options.config_path = matches.opt_str("config-path").map(PathBuf::from);
-        if let Some(ref emit_str) = matches.opt_str("emit") {
+        if options.check || options.files_with_diff {
+            options.emit_mode = Some(EmitMode::Diff);
+        } else if let Some(ref emit_str) = matches.opt_str("emit") {
             options.emit_mode = Some(emit_mode_from_emit_str(emit_str)?);
         }
         options.inline_config = matches
@@ -387,8 +1130,16 @@ impl GetOptsOptions {
                 |key_val| match key_val.char_indices().find(|(_, ch)| *ch == '=') {
                     Some((middle, _)) => {
                         let (key, val) = (&key_val[..middle], &key_val[middle + 1..]);
-                        if !Config::is_valid_key_val(key, val) {
+                        if !Config::is_valid_name(key) {
+                            Err(format_err!("{}", unknown_option_message(key)))
+                        } else if !Config::is_valid_key_val(key, val) {
                             Err(format_err!("invalid key=val pair: `{}`", key_val))
+                        } else if key == "emit_mode"
+                            && val
+                                .parse::<EmitMode>()
+                                .map_or(false, EmitMode::is_machine_readable)
+                        {
+                            Err(format_err!("`{}` can only be set by `--emit`", key_val))
                         } else {
                             Ok((key.to_string(), val.to_string()))
                         }
@@ -434,6 +1185,10 @@ fn emit_mode_from_emit_str(emit_str: &str) -> Result<EmitMode> {
         "new_files" => Ok(EmitMode::NewFiles),
         "stdout" => Ok(EmitMode::Stdout),
         "check_diff" => Ok(EmitMode::Diff),
+        "json" => Ok(EmitMode::Json),
+        "checkstyle" => Ok(EmitMode::Checkstyle),
+        "sarif" => Ok(EmitMode::Sarif),
+        "patch" => Ok(EmitMode::Patch),
         _ => Err(format_err!("Invalid value for `--emit`")),
     }
 }
@@ -1,4 +1,4 @@
-use crate::config::options::IgnoreList;
+use crate::config::options::{IgnoreList, OverrideList};
 
 /// Trait for types that can be used in `Config`.
 pub trait ConfigType: Sized {
@@ -45,6 +45,12 @@ impl ConfigType for IgnoreList {
     }
 }
 
+impl ConfigType for OverrideList {
+    fn doc_hint() -> String {
+        String::from("[[overrides]] paths = [<string>,..] <option> = <value>..")
+    }
+}
+
 macro_rules! create_config {
     // Options passed in to the macro.
     //
@@ -135,7 +141,12 @@ macro_rules! create_config {
                 ConfigWasSet(self)
             }
 
-            fn fill_from_parsed_config(mut self, parsed: PartialConfig) -> Config {
+            fn fill_from_parsed_config(mut self, parsed: PartialConfig, dir: &Path) -> Config {
+                // The paths of `ignore` and `overrides` are relative to the
+                // directory of the file setting them, which may not be the
+                // file inheriting them.
+                let has_ignore = parsed.ignore.is_some();
+                let has_overrides = parsed.overrides.is_some();
             $(
                 if let Some(option_value) = parsed.$i {
                     let option_stable = self.$i.3;
@@ -147,6 +158,12 @@ macro_rules! create_config {
                     }
                 }
             )+
+                if has_ignore {
+                    self.ignore.2.add_prefix(dir);
+                }
+                if has_overrides {
+                    self.overrides.2.add_prefix(dir);
+                }
                 self
             }
 
@@ -160,6 +177,12 @@ macro_rules! create_config {
                 hash_set
             }
 
+            /// Returns the name of every option, in declaration order.
+            #[allow(unreachable_pub)]
+            pub fn option_names() -> &'static [&'static str] {
+                &[$(stringify!($i)),+]
+            }
+
             pub fn is_valid_name(name: &str) -> bool {
                 match name {
                     $(
@@ -201,6 +224,53 @@ macro_rules! create_config {
                 }
             }
 
+            /// Returns the options that were set, by a config file or `--config`,
+            /// to a value other than their default.
+            #[allow(unreachable_pub)]
+            pub fn minimal_options(&self) -> PartialConfig {
+                PartialConfig {
+                    $(
+                        $i: if self.$i.1 && self.$i.2 != $def {
+                                Some(self.$i.2.clone())
+                            } else {
+                                None
+                            },
+                    )+
+                }
+            }
+
+            /// Writes the name, type, default value, stability and description
+            /// of every option to `out`.
+            #[allow(unreachable_pub)]
+            pub fn print_docs(out: &mut dyn std::io::Write) -> std::io::Result<()> {
+                use std::cmp;
+                let max = 0;
+                $( let max = cmp::max(max, stringify!($i).len() + 1); )+
+                let space_str = " ".repeat(max);
+                writeln!(out, "Configuration Options:")?;
+                $(
+                    let name_raw = stringify!($i);
+                    let mut default_str = format!("{}", $def);
+                    if default_str.is_empty() {
+                        default_str = String::from("\"\"");
+                    }
+                    writeln!(
+                        out,
+                        "{}{}{} Default: {}{}",
+                        name_raw,
+                        &space_str[name_raw.len()..],
+                        <$ty>::doc_hint(),
+                        default_str,
+                        if $stb { "" } else { " (unstable)" }
+                    )?;
+                    $(
+                        writeln!(out, "{}{}", space_str, $dstring)?;
+                    )+
+                    writeln!(out)?;
+                )+
+                Ok(())
+            }
+
             #[allow(unreachable_pub)]
             pub fn override_value(&mut self, key: &str, val: &str)
             {
@@ -0,0 +1,230 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::config::{Config, NewlineStyle};
+
+const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// The properties of `.editorconfig` files that map onto movefmt options.
+#[derive(Default, Debug, PartialEq)]
+struct Properties {
+    indent_style: Option<String>,
+    indent_size: Option<String>,
+    tab_width: Option<String>,
+    max_line_length: Option<String>,
+    end_of_line: Option<String>,
+}
+
+impl Properties {
+    fn set(&mut self, key: &str, value: &str) {
+        let property = match key {
+            "indent_style" => &mut self.indent_style,
+            "indent_size" => &mut self.indent_size,
+            "tab_width" => &mut self.tab_width,
+            "max_line_length" => &mut self.max_line_length,
+            "end_of_line" => &mut self.end_of_line,
+            _ => return,
+        };
+        // `unset` removes the value set by a previous section or file.
+        *property = if value == "unset" {
+            None
+        } else {
+            Some(value.to_string())
+        };
+    }
+}
+
+/// Sets the options not set by a movefmt.toml from the `.editorconfig` files of
+/// `dir` and its parents, up to the one with `root = true`.
+///
+/// Only the sections matching every `.move` file are read, such as `[*]`,
+/// `[*.move]`, `[*.{move,toml}]` or `[{*.move,*.toml}]`.
+pub(super) fn apply_editorconfig(config: &mut Config, dir: &Path) -> Result<(), Error> {
+    let dir = match dir.canonicalize() {
+        Ok(dir) => dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let mut contents = vec![];
+    for dir in dir.ancestors() {
+        let content = match fs::read_to_string(dir.join(EDITORCONFIG_FILE_NAME)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let is_root = is_root(&content);
+        contents.push(content);
+        if is_root {
+            break;
+        }
+    }
+
+    // The nearest file takes precedence, so it's read last.
+    let mut properties = Properties::default();
+    for content in contents.iter().rev() {
+        read_move_properties(content, &mut properties);
+    }
+
+    let Properties {
+        indent_style,
+        indent_size,
+        tab_width,
+        max_line_length,
+        end_of_line,
+    } = properties;
+    match indent_style.as_deref() {
+        Some("tab") if !config.was_set().hard_tabs() => config.set().hard_tabs(true),
+        Some("space") if !config.was_set().hard_tabs() => config.set().hard_tabs(false),
+        _ => {}
+    }
+    let tab_width = tab_width.and_then(|width| width.parse::<usize>().ok());
+    if let Some(tab_width) = tab_width {
+        if !config.was_set().tab_spaces() {
+            config.set().tab_spaces(tab_width);
+        }
+    }
+    // `indent_size = tab` indents with `tab_width` columns.
+    let indent_size = match indent_size.as_deref() {
+        Some("tab") => tab_width,
+        Some(size) => size.parse::<usize>().ok(),
+        None => None,
+    };
+    if let Some(indent_size) = indent_size {
+        if !config.was_set().indent_size() {
+            config.set().indent_size(indent_size);
+        }
+    }
+    // `max_line_length = off` is not a number, so it's skipped.
+    if let Some(max_width) = max_line_length.and_then(|width| width.parse::<usize>().ok()) {
+        if !config.was_set().max_width() {
+            config.set().max_width(max_width);
+        }
+    }
+    let newline_style = match end_of_line.as_deref() {
+        Some("lf") => Some(NewlineStyle::Unix),
+        Some("crlf") => Some(NewlineStyle::Windows),
+        _ => None,
+    };
+    if let Some(newline_style) = newline_style {
+        if !config.was_set().newline_style() {
+            config.set().newline_style(newline_style);
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if the preamble of an `.editorconfig` has `root = true`.
+fn is_root(content: &str) -> bool {
+    for line in content.lines() {
+        match parse_line(line) {
+            Line::Section(_) => return false,
+            Line::Property(key, value) if key == "root" => return value == "true",
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Reads the properties of the sections of an `.editorconfig` that apply to
+/// every `.move` file into `properties`, the later sections taking precedence.
+fn read_move_properties(content: &str, properties: &mut Properties) {
+    let mut in_move_section = false;
+    for line in content.lines() {
+        match parse_line(line) {
+            Line::Section(glob) => in_move_section = matches_all_move_files(glob),
+            Line::Property(key, value) if in_move_section => properties.set(&key, &value),
+            _ => {}
+        }
+    }
+}
+
+enum Line<'a> {
+    Section(&'a str),
+    /// A property, with its key and value in lowercase.
+    Property(String, String),
+    Other,
+}
+
+fn parse_line(line: &str) -> Line<'_> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        return Line::Other;
+    }
+    if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+        return Line::Section(glob);
+    }
+    match line.split_once('=') {
+        Some((key, value)) => {
+            Line::Property(key.trim().to_lowercase(), value.trim().to_lowercase())
+        }
+        None => Line::Other,
+    }
+}
+
+/// Returns `true` if the section glob matches every `.move` file, whatever its
+/// name and directory.
+fn matches_all_move_files(glob: &str) -> bool {
+    expand_braces(glob).iter().any(|glob| {
+        matches!(
+            glob.trim(),
+            "*" | "**" | "*.move" | "**.move" | "**/*.move" | "/**/*.move"
+        )
+    })
+}
+
+/// Expands the `{a,b}` alternatives of `glob`, e.g. `*.{rs,move}` into `*.rs`
+/// and `*.move`. Nested braces are not supported.
+fn expand_braces(glob: &str) -> Vec<String> {
+    let (start, end) = match (glob.find('{'), glob.find('}')) {
+        (Some(start), Some(end)) if start < end => (start, end),
+        _ => return vec![glob.to_string()],
+    };
+    let (prefix, suffix) = (&glob[..start], &glob[end + 1..]);
+    glob[start + 1..end]
+        .split(',')
+        .flat_map(|alternative| expand_braces(&format!("{prefix}{alternative}{suffix}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_sections_of_move_files() {
+        let content = "root = true\n\
+                       \n\
+                       [*]\n\
+                       indent_style = space\n\
+                       indent_size = 2\n\
+                       \n\
+                       [{*.move,*.toml}]\n\
+                       indent_size = 4\n\
+                       max_line_length = 100\n\
+                       \n\
+                       [*.{rs,move}]\n\
+                       tab_width = 8\n\
+                       \n\
+                       [*.rs]\n\
+                       max_line_length = 80\n\
+                       \n\
+                       [sources/a.move]\n\
+                       end_of_line = crlf\n";
+        assert!(is_root(content));
+        assert!(matches_all_move_files("**/*.{move,toml}"));
+        assert!(!matches_all_move_files("sources/*.{move,toml}"));
+        let mut properties = Properties::default();
+        read_move_properties(content, &mut properties);
+        assert_eq!(
+            properties,
+            Properties {
+                indent_style: Some("space".to_string()),
+                indent_size: Some("4".to_string()),
+                tab_width: Some("8".to_string()),
+                max_line_length: Some("100".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -1,18 +1,22 @@
 use std::cell::Cell;
+use std::cmp;
 use std::default::Default;
 use std::fs::File;
 use std::io::{Error, ErrorKind, Read};
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+use semver::{Version, VersionReq};
 use thiserror::Error;
 
 use crate::config::config_type::ConfigType;
 #[allow(unreachable_pub)]
 pub use crate::config::options::*;
+use crate::ignore_path::IgnorePathSet;
 
 #[macro_use]
 pub mod config_type;
+mod editorconfig;
 #[macro_use]
 #[allow(unreachable_pub)]
 pub mod options;
@@ -29,8 +33,28 @@ create_config! {
     emit_mode: EmitMode, EmitMode::Files, true,
         "What emit Mode to use when none is supplied";
     verbose: Verbosity, Verbosity::Normal, true, "How much to information to emit to the user";
+    ignore: IgnoreList, IgnoreList::default(), true,
+        "Skip formatting the specified files and directories";
+    newline_style: NewlineStyle, NewlineStyle::Auto, true, "Unix or Windows line endings";
+    error_on_line_overflow: bool, false, true,
+        "Error if the output has lines wider than max_width";
+    error_on_unformatted: bool, false, true,
+        "Error if the output has lines with trailing whitespace";
+    required_version: String, String::new(), true,
+        "Require a version of movefmt matching this semver requirement, e.g. \">=1.1\"";
+    inherit: bool, false, true,
+        "Take the options not set in this file from the config of the parent directories";
+    overrides: OverrideList, OverrideList::default(), true,
+        "Options for the files matching some globs, in [[overrides]] sections";
 }
 
+/// The manifest of a Move package, whose `[movefmt]` table is read as a config.
+const MOVE_MANIFEST_NAME: &str = "Move.toml";
+const MOVEFMT_TABLE_NAME: &str = "movefmt";
+
+/// Options that apply to a whole config file, so they can't be set in `[[overrides]]`.
+const NON_OVERRIDABLE_OPTIONS: [&str; 4] = ["ignore", "required_version", "inherit", "overrides"];
+
 #[derive(Error, Debug)]
 #[error("Could not output config: {0}")]
 pub struct ToTomlError(toml::ser::Error);
@@ -52,11 +76,48 @@ impl Config {
     /// Returns a `Config` if the config could be read and parsed from
     /// the file, otherwise errors.
     pub(super) fn from_toml_path(file_path: &Path) -> Result<Config, Error> {
+        Config::from_toml_path_inheriting(file_path, &mut vec![])
+    }
+
+    /// Like `from_toml_path`, where `visited` are the files inheriting from
+    /// `file_path`, which are skipped when looking for the parent config.
+    fn from_toml_path_inheriting(
+        file_path: &Path,
+        visited: &mut Vec<PathBuf>,
+    ) -> Result<Config, Error> {
         let mut file = File::open(&file_path)?;
         let mut toml = String::new();
         file.read_to_string(&mut toml)?;
-        Config::from_toml(&toml)
-            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+        let file_path = fs::canonicalize(file_path)?;
+        // Paths in the `ignore` list are relative to the directory of the config file.
+        let dir = file_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let parsed = if file_path.ends_with(MOVE_MANIFEST_NAME) {
+            // Only the `[movefmt]` table of a Move.toml is a config.
+            let table = movefmt_table(&toml)?.unwrap_or_default();
+            partial_config_from_value(toml::Value::Table(table))
+        } else {
+            parse_partial_config(&toml)
+        }
+        .map_err(|err| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{}: {}", file_path.display(), err),
+            )
+        })?;
+
+        visited.push(file_path);
+        let base = if parsed.inherit == Some(true) {
+            match resolve_parent_file(&dir, visited)? {
+                Some(parent) => Config::from_toml_path_inheriting(&parent, visited)?,
+                None => Config::default(),
+            }
+        } else {
+            Config::default()
+        };
+        Ok(base.fill_from_parsed_config(parsed, &dir))
     }
 
     /// Resolves the config for input in `dir`.
@@ -69,84 +130,246 @@ impl Config {
     /// Returns the `Config` to use, and the path of the project file if there was
     /// one.
     pub(super) fn from_resolved_toml_path(dir: &Path) -> Result<(Config, Option<PathBuf>), Error> {
-        /// Try to find a project file in the given directory and its parents.
-        /// Returns the path of a the nearest project file if one exists,
-        /// or `None` if no project file was found.
-        fn resolve_project_file(dir: &Path) -> Result<Option<PathBuf>, Error> {
-            let mut current = if dir.is_relative() {
-                env::current_dir()?.join(dir)
-            } else {
-                dir.to_path_buf()
-            };
-
-            current = fs::canonicalize(current)?;
+        match resolve_project_file(dir)? {
+            None => Ok((Config::default(), None)),
+            Some(path) => Config::from_toml_path(&path).map(|config| (config, Some(path))),
+        }
+    }
 
-            loop {
-                match get_toml_path(&current) {
-                    Ok(Some(path)) => return Ok(Some(path)),
-                    Err(e) => return Err(e),
-                    _ => (),
-                }
+    pub fn from_toml(toml: &str, dir: &Path) -> Result<Config, String> {
+        let parsed = parse_partial_config(toml)?;
+        Ok(Config::default().fill_from_parsed_config(parsed, dir))
+    }
 
-                // If the current directory has no parent, we're done searching.
-                if !current.pop() {
-                    break;
-                }
+    /// Returns the config to use for `file`: this config with the options of
+    /// every `[[overrides]]` section matching `file` applied in order.
+    pub fn apply_path_overrides(&self, file: &Path) -> Result<Config, crate::ErrorKind> {
+        let mut config = self.clone();
+        let overrides = &self.overrides.2;
+        if overrides.is_empty() {
+            return Ok(config);
+        }
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        for section in overrides {
+            let paths = IgnoreList::from_paths(
+                overrides.movefmt_toml_path(),
+                section.paths.iter().map(PathBuf::from),
+            );
+            let path_set = IgnorePathSet::from_ignore_list(&paths)
+                .map_err(crate::ErrorKind::InvalidGlobPattern)?;
+            if !path_set.is_match(&file) {
+                continue;
             }
-
-            // If nothing was found, check in the home directory.
-            if let Some(home_dir) = dirs::home_dir() {
-                if let Some(path) = get_toml_path(&home_dir)? {
-                    return Ok(Some(path));
-                }
+            // The options were checked when the config was loaded.
+            if let Ok(parsed) = toml::Value::Table(section.options.clone()).try_into() {
+                config = config.fill_from_parsed_config(parsed, overrides.movefmt_toml_path());
             }
+        }
+        Ok(config)
+    }
 
-            // If none was found ther either, check in the user's configuration directory.
-            if let Some(mut config_dir) = dirs::config_dir() {
-                config_dir.push("movefmt");
-                if let Some(path) = get_toml_path(&config_dir)? {
-                    return Ok(Some(path));
-                }
-            }
+    /// Returns the width of one level of indentation: a tab of `tab_spaces`
+    /// columns with `hard_tabs`, `indent_size` spaces otherwise.
+    pub fn indent_width(&self) -> usize {
+        if self.hard_tabs() {
+            self.tab_spaces()
+        } else {
+            self.indent_size()
+        }
+    }
 
-            Ok(None)
+    /// Checks that `version` of movefmt satisfies the `required_version` option.
+    /// An empty `required_version` accepts every version.
+    pub fn version_meets_requirement(&self, version: &str) -> Result<(), crate::ErrorKind> {
+        let required_version = self.required_version();
+        if required_version.is_empty() {
+            return Ok(());
         }
+        let mismatch =
+            || crate::ErrorKind::VersionMismatch(required_version.clone(), version.to_string());
+        let req = VersionReq::parse(&required_version).map_err(|_| mismatch())?;
+        let version = Version::parse(version).map_err(|_| mismatch())?;
+        if req.matches(&version) {
+            Ok(())
+        } else {
+            Err(mismatch())
+        }
+    }
+}
 
-        match resolve_project_file(dir)? {
-            None => Ok((Config::default(), None)),
-            Some(path) => Config::from_toml_path(&path).map(|config| (config, Some(path))),
+/// Parses the content of a movefmt.toml, rejecting unknown options and invalid
+/// values.
+fn parse_partial_config(toml: &str) -> Result<PartialConfig, String> {
+    let parsed: ::toml::Value = toml
+        .parse()
+        .map_err(|e| format!("Could not parse TOML: {}", e))?;
+    partial_config_from_value(parsed)
+}
+
+/// Converts the options of a movefmt.toml, or of the `[movefmt]` table of a
+/// Move.toml, rejecting unknown options and invalid values.
+fn partial_config_from_value(parsed: toml::Value) -> Result<PartialConfig, String> {
+    let table = parsed
+        .as_table()
+        .ok_or_else(|| String::from("Parsed config was not table"))?;
+    let mut err = String::new();
+    for key in table.keys() {
+        if !Config::is_valid_name(key) {
+            err.push_str(&unknown_option_message(key));
+            err.push('\n');
+        }
+    }
+    if !err.is_empty() {
+        err.push_str("Please check your config file.");
+        return Err(err);
+    }
+    let parsed_config: PartialConfig = parsed.try_into().map_err(|e| {
+        format!("Decoding config file failed:\n{e}\nPlease check your config file.")
+    })?;
+    if let Some(required_version) = &parsed_config.required_version {
+        if !required_version.is_empty() {
+            VersionReq::parse(required_version)
+                .map_err(|e| format!("Invalid `required_version = \"{required_version}\"`: {e}"))?;
         }
     }
+    if let Some(ignore) = &parsed_config.ignore {
+        IgnorePathSet::from_ignore_list(ignore)
+            .map_err(|e| format!("Invalid glob pattern in `ignore`: {e}"))?;
+    }
+    if let Some(overrides) = &parsed_config.overrides {
+        for section in overrides {
+            check_override(section)?;
+        }
+    }
+    check_emit_mode(&parsed_config)?;
+    Ok(parsed_config)
+}
 
-    pub fn from_toml(toml: &str) -> Result<Config, String> {
-        let parsed: ::toml::Value = toml
-            .parse()
-            .map_err(|e| format!("Could not parse TOML: {}", e))?;
-        let mut err = String::new();
-        let table = parsed
-            .as_table()
-            .ok_or_else(|| String::from("Parsed config was not table"))?;
-        for key in table.keys() {
-            if !Config::is_valid_name(key) {
-                let msg = &format!("Warning: Unknown configuration option `{key}`\n");
-                err.push_str(msg)
-            }
+/// Rejects the machine readable emit modes, which write a report instead of
+/// the files and so must be asked for by `--emit`.
+fn check_emit_mode(parsed_config: &PartialConfig) -> Result<(), String> {
+    match parsed_config.emit_mode {
+        Some(emit_mode) if emit_mode.is_machine_readable() => Err(format!(
+            "`emit_mode = \"{emit_mode}\"` can only be set by `--emit`"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the options and globs of an `[[overrides]]` section.
+fn check_override(section: &Override) -> Result<(), String> {
+    for key in section.options.keys() {
+        if !Config::is_valid_name(key) {
+            return Err(unknown_option_message(key));
         }
-        match parsed.try_into() {
-            Ok(parsed_config) => {
-                if !err.is_empty() {
-                    eprint!("{err}");
-                }
-                Ok(Config::default().fill_from_parsed_config(parsed_config))
-            }
-            Err(e) => {
-                err.push_str("Error: Decoding config file failed:\n");
-                err.push_str(format!("{e}\n").as_str());
-                err.push_str("Please check your config file.");
-                Err(err)
+        if NON_OVERRIDABLE_OPTIONS.contains(&key.as_str()) {
+            return Err(format!("`{key}` can't be set in `[[overrides]]`"));
+        }
+    }
+    let options = toml::Value::Table(section.options.clone())
+        .try_into::<PartialConfig>()
+        .map_err(|e| format!("Decoding `[[overrides]]` failed:\n{e}"))?;
+    check_emit_mode(&options)?;
+    let paths = IgnoreList::from_paths(Path::new(""), section.paths.iter().map(PathBuf::from));
+    IgnorePathSet::from_ignore_list(&paths)
+        .map_err(|e| format!("Invalid glob pattern in `[[overrides]]`: {e}"))?;
+    Ok(())
+}
+
+/// Returns the directories searched for a config file for input in `dir`, in
+/// order: `dir` and its parents, the home directory, and the `movefmt`
+/// directory of the user's configuration directory.
+fn config_search_dirs(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let current = if dir.is_relative() {
+        env::current_dir()?.join(dir)
+    } else {
+        dir.to_path_buf()
+    };
+    let current = fs::canonicalize(current)?;
+
+    let mut search_dirs: Vec<PathBuf> = current.ancestors().map(Path::to_path_buf).collect();
+    if let Some(home_dir) = dirs::home_dir() {
+        search_dirs.push(home_dir);
+    }
+    if let Some(config_dir) = dirs::config_dir() {
+        search_dirs.push(config_dir.join("movefmt"));
+    }
+    // The home directory may also be a parent of `dir`.
+    let mut seen = std::collections::HashSet::new();
+    search_dirs.retain(|dir| seen.insert(dir.clone()));
+    Ok(search_dirs)
+}
+
+/// Try to find a project file in the given directory and its parents.
+/// Returns the path of a the nearest project file if one exists,
+/// or `None` if no project file was found.
+fn resolve_project_file(dir: &Path) -> Result<Option<PathBuf>, Error> {
+    for dir in config_search_dirs(dir)? {
+        if let Some(path) = get_toml_path(&dir)? {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+/// Finds the config that the config file in `dir` inherits from: the next one
+/// found by the search for a config, which may be the Move.toml next to it.
+/// The files in `visited` are skipped, so a config never inherits from itself.
+fn resolve_parent_file(dir: &Path, visited: &[PathBuf]) -> Result<Option<PathBuf>, Error> {
+    for search_dir in config_search_dirs(dir)? {
+        let candidates = [
+            get_movefmt_toml_path(&search_dir)?,
+            get_manifest_path(&search_dir)?,
+        ];
+        for path in candidates.into_iter().flatten() {
+            if !visited.iter().any(|visited| same_file(visited, &path)) {
+                return Ok(Some(path));
             }
         }
     }
+    Ok(None)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    fs::canonicalize(b).map_or(false, |b| a == b)
+}
+
+/// Returns the error message for the unknown option `name`, suggesting the
+/// closest option name if there is one.
+pub fn unknown_option_message(name: &str) -> String {
+    match suggest_option_name(name) {
+        Some(suggestion) => {
+            format!("Unknown configuration option `{name}`, did you mean `{suggestion}`?")
+        }
+        None => format!("Unknown configuration option `{name}`"),
+    }
+}
+
+/// Returns the option name closest to `name`, if it's close enough to be a typo.
+fn suggest_option_name(name: &str) -> Option<&'static str> {
+    let max_distance = cmp::max(name.chars().count() / 3, 1);
+    Config::option_names()
+        .iter()
+        .map(|option| (edit_distance(name, option), *option))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// Computes the Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = cmp::min(substitution, cmp::min(row[j], row[j + 1]) + 1);
+        }
+    }
+    row[b.len()]
 }
 
 /// Loads a config by checking the client-supplied options and if appropriate, the
@@ -160,26 +383,36 @@ pub fn load_config<O: CliOptions>(
         None => None,
     };
 
-    let result = if let Some(over_ride) = over_ride {
-        Config::from_toml_path(over_ride.as_ref()).map(|p| (p, Some(over_ride.to_owned())))
+    let (mut config, path) = if let Some(over_ride) = over_ride {
+        // `--config-path` is the only config, so `.editorconfig` is not read.
+        Config::from_toml_path(over_ride.as_ref()).map(|p| (p, Some(over_ride.to_owned())))?
     } else if let Some(file_path) = file_path {
-        Config::from_resolved_toml_path(file_path)
+        let (mut config, path) = Config::from_resolved_toml_path(file_path)?;
+        // `.editorconfig` only sets the options that the config file leaves unset.
+        editorconfig::apply_editorconfig(&mut config, file_path)?;
+        (config, path)
     } else {
-        Ok((Config::default(), None))
+        (Config::default(), None)
     };
 
-    result.map(|(mut c, p)| {
-        if let Some(options) = options {
-            options.apply_to(&mut c);
-        }
-        (c, p)
-    })
+    if let Some(options) = options {
+        options.apply_to(&mut config);
+    }
+    Ok((config, path))
 }
 
-// Check for the presence of known config file names (`movefmt.toml, `.movefmt.toml`) in `dir`
+// Check for the presence of known config file names (`movefmt.toml, `.movefmt.toml`) in `dir`,
+// or else of a Move.toml with a `[movefmt]` table
 //
 // Return the path if a config file exists, empty if no file exists, and Error for IO errors
 fn get_toml_path(dir: &Path) -> Result<Option<PathBuf>, Error> {
+    match get_movefmt_toml_path(dir)? {
+        Some(path) => Ok(Some(path)),
+        None => get_manifest_path(dir),
+    }
+}
+
+fn get_movefmt_toml_path(dir: &Path) -> Result<Option<PathBuf>, Error> {
     const CONFIG_FILE_NAMES: [&str; 2] = [".movefmt.toml", "movefmt.toml"];
     for config_file_name in &CONFIG_FILE_NAMES {
         let config_file = dir.join(config_file_name);
@@ -202,12 +435,38 @@ fn get_toml_path(dir: &Path) -> Result<Option<PathBuf>, Error> {
     Ok(None)
 }
 
+/// Returns the path of the Move.toml in `dir` if it has a `[movefmt]` table.
+fn get_manifest_path(dir: &Path) -> Result<Option<PathBuf>, Error> {
+    let manifest = dir.join(MOVE_MANIFEST_NAME);
+    if manifest.is_file() && movefmt_table(&fs::read_to_string(&manifest)?)?.is_some() {
+        return Ok(Some(manifest));
+    }
+    Ok(None)
+}
+
+/// Returns the `[movefmt]` table of the content of a Move.toml, if it has one.
+fn movefmt_table(manifest: &str) -> Result<Option<toml::value::Table>, Error> {
+    // An invalid manifest is reported by the Move tools, only the table matters here.
+    let manifest: toml::Value = match manifest.parse() {
+        Ok(manifest) => manifest,
+        Err(_) => return Ok(None),
+    };
+    match manifest.get(MOVEFMT_TABLE_NAME) {
+        None => Ok(None),
+        Some(toml::Value::Table(table)) => Ok(Some(table.clone())),
+        Some(_) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("`{MOVEFMT_TABLE_NAME}` in {MOVE_MANIFEST_NAME} must be a table"),
+        )),
+    }
+}
+
 fn config_path(options: &dyn CliOptions) -> Result<Option<PathBuf>, Error> {
     let config_path_not_found = |path: &str| -> Result<Option<PathBuf>, Error> {
         Err(Error::new(
             ErrorKind::NotFound,
             format!(
-                "Error: unable to find a config file for the given path: `{}`",
+                "unable to find a config file for the given path: `{}`",
                 path
             ),
         ))
@@ -228,3 +487,173 @@ fn config_path(options: &dyn CliOptions) -> Result<Option<PathBuf>, Error> {
         path => Ok(path.map(ToOwned::to_owned)),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unknown_option_is_an_error() {
+        let err = Config::from_toml("max_widht = 100", Path::new("")).unwrap_err();
+        assert!(err.contains("Unknown configuration option `max_widht`, did you mean `max_width`?"));
+        let err = Config::from_toml("foo = 1", Path::new("")).unwrap_err();
+        assert!(err.contains("Unknown configuration option `foo`\n"));
+    }
+
+    #[test]
+    fn required_version() {
+        let config = Config::from_toml("required_version = \">=1.1\"", Path::new("")).unwrap();
+        assert!(config.version_meets_requirement("1.1.3").is_ok());
+        assert!(matches!(
+            config.version_meets_requirement("1.0.2"),
+            Err(crate::ErrorKind::VersionMismatch(..))
+        ));
+        assert!(Config::default().version_meets_requirement("0.1.0").is_ok());
+        assert!(Config::from_toml("required_version = \"one\"", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn machine_readable_emit_mode_is_an_error() {
+        let err = Config::from_toml("emit_mode = \"Json\"", Path::new("")).unwrap_err();
+        assert!(err.contains("can only be set by `--emit`"));
+        let toml = "[[overrides]]\npaths = [\"tests/**\"]\nemit_mode = \"Patch\"";
+        assert!(Config::from_toml(toml, Path::new("")).is_err());
+        let config = Config::from_toml("emit_mode = \"Diff\"", Path::new("")).unwrap();
+        assert_eq!(config.emit_mode(), EmitMode::Diff);
+    }
+
+    #[test]
+    fn overrides_apply_to_matching_paths() {
+        let toml = r#"
+            max_width = 90
+
+            [[overrides]]
+            paths = ["tests/**"]
+            max_width = 120
+        "#;
+        let dir = Path::new("/no/such/dir");
+        let config = Config::from_toml(toml, dir).unwrap();
+        let test_config = config
+            .apply_path_overrides(&dir.join("tests/a.move"))
+            .unwrap();
+        assert_eq!(test_config.max_width(), 120);
+        let source_config = config
+            .apply_path_overrides(&dir.join("sources/a.move"))
+            .unwrap();
+        assert_eq!(source_config.max_width(), 90);
+
+        let toml = "[[overrides]]\npaths = [\"tests/**\"]\nignore = []";
+        assert!(Config::from_toml(toml, dir).is_err());
+    }
+
+    #[test]
+    fn ignore_is_relative_to_config_dir() {
+        let root = env::temp_dir().join(format!("movefmt-ignore-{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        let root = root.canonicalize().unwrap();
+        fs::write(root.join("movefmt.toml"), "ignore = [\"sources/a.move\"]").unwrap();
+        let (config, _) = Config::from_resolved_toml_path(&root.join("sub")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        let ignore_set = IgnorePathSet::from_ignore_list(&config.ignore()).unwrap();
+        assert!(ignore_set.is_match(&root.join("sources/a.move")));
+        assert!(!ignore_set.is_match(&root.join("sub/sources/a.move")));
+        assert!(!ignore_set.is_match(&root.join("sources/b.move")));
+
+        assert!(Config::from_toml("ignore = [\"sources/[a\"]", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn inherit_options_of_parent_config() {
+        let root = env::temp_dir().join(format!("movefmt-inherit-{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("movefmt.toml"), "max_width = 100\ntab_spaces = 2").unwrap();
+        fs::write(
+            root.join("sub/movefmt.toml"),
+            "inherit = true\nmax_width = 120",
+        )
+        .unwrap();
+        let (config, _) = Config::from_resolved_toml_path(&root.join("sub")).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(config.max_width(), 120);
+        assert_eq!(config.tab_spaces(), 2);
+    }
+
+    #[test]
+    fn movefmt_table_of_move_toml() {
+        let root = env::temp_dir().join(format!("movefmt-manifest-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("Move.toml"),
+            "[package]\nname = \"a\"\n\n[movefmt]\nmax_width = 100",
+        )
+        .unwrap();
+        let (config, path) = Config::from_resolved_toml_path(&root).unwrap();
+        assert_eq!(config.max_width(), 100);
+        assert!(path.unwrap().ends_with("Move.toml"));
+
+        // A movefmt.toml next to Move.toml takes precedence.
+        fs::write(root.join("movefmt.toml"), "max_width = 110").unwrap();
+        let (config, _) = Config::from_resolved_toml_path(&root).unwrap();
+        assert_eq!(config.max_width(), 110);
+
+        // Unless it inherits from it.
+        fs::write(root.join("movefmt.toml"), "inherit = true\ntab_spaces = 2").unwrap();
+        let (config, _) = Config::from_resolved_toml_path(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(config.max_width(), 100);
+        assert_eq!(config.tab_spaces(), 2);
+    }
+
+    struct OutputOptions;
+
+    impl CliOptions for OutputOptions {
+        fn apply_to(self, config: &mut Config) {
+            config.set().verbose(Verbosity::Verbose);
+            config.set().emit_mode(EmitMode::Diff);
+        }
+
+        fn config_path(&self) -> Option<&Path> {
+            None
+        }
+    }
+
+    #[test]
+    fn minimal_options_of_config_file() {
+        let root = env::temp_dir().join(format!("movefmt-minimal-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("movefmt.toml"), "tab_spaces = 2\nmax_width = 90").unwrap();
+        fs::write(
+            root.join(".editorconfig"),
+            "root = true\n[*]\nindent_size = 2\nend_of_line = crlf\n",
+        )
+        .unwrap();
+        let (config, _) = load_config(Some(&root), Some(OutputOptions)).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(config.indent_size(), 2);
+        assert_eq!(config.verbose(), Verbosity::Verbose);
+        // `max_width` is set to its default, the others aren't set by movefmt.toml.
+        assert_eq!(
+            config.minimal_options().to_toml().unwrap(),
+            "tab_spaces = 2\n"
+        );
+    }
+
+    #[test]
+    fn print_docs_of_options() {
+        let mut out = vec![];
+        Config::print_docs(&mut out).unwrap();
+        let docs = String::from_utf8(out).unwrap();
+        let mut lines = docs.lines();
+        assert_eq!(lines.next(), Some("Configuration Options:"));
+        let mut lines = lines.skip_while(|line| !line.starts_with("tab_spaces "));
+        assert_eq!(
+            lines.next().unwrap().split_whitespace().collect::<Vec<_>>(),
+            ["tab_spaces", "<unsigned", "integer>", "Default:", "4"]
+        );
+        assert_eq!(lines.next().unwrap().trim(), "Number of spaces per tab");
+        assert_eq!(
+            docs.matches(" Default: ").count(),
+            Config::option_names().len()
+        );
+    }
+}
@@ -141,6 +141,14 @@ pub enum EmitMode {
     /// This option is designed to be run in CI where a non-zero exit signifies
     /// non-standard code formatting. Used for `--check`.
     Diff,
+    /// Outputs the mismatches of all files as a JSON array.
+    Json,
+    /// Outputs a checkstyle XML report of the mismatches and parse errors.
+    Checkstyle,
+    /// Outputs a SARIF 2.1.0 log of the mismatches and parse errors.
+    Sarif,
+    /// Outputs a unified diff that `patch` and `git apply` accept.
+    Patch,
 }
 
 /// How chatty should movefmt be?
@@ -154,12 +162,36 @@ pub enum Verbosity {
     Quiet,
 }
 
+/// Line endings of the formatted files.
+#[config_type]
+pub enum NewlineStyle {
+    /// Uses the line ending of the first line of the input.
+    Auto,
+    /// `\r\n` on Windows, `\n` on other platforms.
+    Native,
+    /// Forces `\n`.
+    Unix,
+    /// Forces `\r\n`.
+    Windows,
+}
+
 impl Default for EmitMode {
     fn default() -> EmitMode {
         EmitMode::Files
     }
 }
 
+impl EmitMode {
+    /// Returns `true` for the modes writing a single report of the whole run,
+    /// which can only be selected by `--emit`.
+    pub fn is_machine_readable(self) -> bool {
+        matches!(
+            self,
+            EmitMode::Json | EmitMode::Checkstyle | EmitMode::Sarif | EmitMode::Patch
+        )
+    }
+}
+
 /// A set of directories, files and modules that movefmt should ignore.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct IgnoreList {
@@ -238,6 +270,14 @@ impl<'a> IntoIterator for &'a IgnoreList {
 }
 
 impl IgnoreList {
+    /// Creates an `IgnoreList` whose patterns are relative to `dir`.
+    pub fn from_paths<I: IntoIterator<Item = PathBuf>>(dir: &Path, paths: I) -> Self {
+        IgnoreList {
+            path_set: paths.into_iter().collect(),
+            movefmt_toml_path: dir.to_path_buf(),
+        }
+    }
+
     pub fn add_prefix(&mut self, dir: &Path) {
         self.movefmt_toml_path = dir.to_path_buf();
     }
@@ -255,6 +295,88 @@ impl FromStr for IgnoreList {
     }
 }
 
+/// An `[[overrides]]` section: options for the files matching some globs.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Override {
+    /// Gitignore-style globs, relative to the directory of movefmt.toml.
+    pub paths: Vec<String>,
+    /// The options to set, as written in movefmt.toml.
+    #[serde(flatten)]
+    pub options: toml::value::Table,
+}
+
+/// The `[[overrides]]` sections of a movefmt.toml, applied in order.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct OverrideList {
+    overrides: Vec<Override>,
+    /// A path to movefmt.toml.
+    movefmt_toml_path: PathBuf,
+}
+
+impl fmt::Display for OverrideList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}]",
+            self.overrides
+                .iter()
+                .format_with(", ", |o, f| f(&format_args!("{{paths = {:?}}}", o.paths)))
+        )
+    }
+}
+
+impl Serialize for OverrideList {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.overrides.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OverrideList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(OverrideList {
+            overrides: Vec::deserialize(deserializer)?,
+            movefmt_toml_path: PathBuf::new(),
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a OverrideList {
+    type Item = &'a Override;
+    type IntoIter = std::slice::Iter<'a, Override>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.overrides.iter()
+    }
+}
+
+impl OverrideList {
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn add_prefix(&mut self, dir: &Path) {
+        self.movefmt_toml_path = dir.to_path_buf();
+    }
+
+    pub fn movefmt_toml_path(&self) -> &Path {
+        &self.movefmt_toml_path
+    }
+}
+
+impl FromStr for OverrideList {
+    type Err = &'static str;
+
+    fn from_str(_: &str) -> Result<Self, Self::Err> {
+        Err("OverrideList is not parsable")
+    }
+}
+
 /// Maps client-supplied options to movefmt's internals, mostly overriding
 /// values in a config with values from the command line.
 pub trait CliOptions {
@@ -0,0 +1,134 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::{Config, ErrorKind};
+
+/// A problem left in the output of the formatter.
+#[derive(Debug)]
+pub struct FormattingError {
+    /// 1-based line of the problem.
+    pub line: usize,
+    /// 1-based column, in characters, where the problem starts.
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl FormattingError {
+    /// Returns `true` if `config` turns this warning into a failure.
+    pub fn is_error(&self, config: &Config) -> bool {
+        match self.kind {
+            ErrorKind::LineOverflow(..) => config.error_on_line_overflow(),
+            ErrorKind::TrailingWhitespace => config.error_on_unformatted(),
+            _ => true,
+        }
+    }
+}
+
+/// Scans formatted text for lines wider than `max_width` and for trailing
+/// whitespace. A tab is as wide as `tab_spaces` columns.
+pub fn check_formatted_text(text: &str, config: &Config) -> Vec<FormattingError> {
+    let mut errors = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let mut width = 0;
+        let mut overflow_column = None;
+        for (column, ch) in line.chars().enumerate() {
+            width += match ch {
+                '\t' => config.tab_spaces(),
+                _ => ch.width().unwrap_or(0),
+            };
+            if width > config.max_width() && overflow_column.is_none() {
+                overflow_column = Some(column + 1);
+            }
+        }
+        if let Some(column) = overflow_column {
+            errors.push(FormattingError {
+                line: index + 1,
+                column,
+                kind: ErrorKind::LineOverflow(width, config.max_width()),
+            });
+        }
+
+        let trimmed = line.trim_end();
+        if trimmed.len() < line.len() {
+            errors.push(FormattingError {
+                line: index + 1,
+                column: trimmed.chars().count() + 1,
+                kind: ErrorKind::TrailingWhitespace,
+            });
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(max_width: usize, tab_spaces: usize) -> Config {
+        let mut config = Config::default();
+        config.set().max_width(max_width);
+        config.set().tab_spaces(tab_spaces);
+        config
+    }
+
+    #[test]
+    fn column_of_line_overflow() {
+        let errors = check_formatted_text("short\nlet x = 123;\n", &config(8, 4));
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (2, 9));
+        assert!(matches!(errors[0].kind, ErrorKind::LineOverflow(12, 8)));
+    }
+
+    #[test]
+    fn tab_is_tab_spaces_columns() {
+        let errors = check_formatted_text("\tabc\n", &config(6, 4));
+        assert!(matches!(errors[0].kind, ErrorKind::LineOverflow(7, 6)));
+        assert_eq!(errors[0].column, 4);
+        assert!(check_formatted_text("\tabc\n", &config(6, 2)).is_empty());
+    }
+
+    #[test]
+    fn wide_characters() {
+        // Each of these characters is 2 columns wide.
+        let errors = check_formatted_text("// 中文字\n", &config(8, 4));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].column, 6);
+        assert!(matches!(errors[0].kind, ErrorKind::LineOverflow(9, 8)));
+    }
+
+    #[test]
+    fn trailing_whitespace_and_carriage_return() {
+        let errors = check_formatted_text("a;  \r\nb;\r\nc;\r", &config(90, 4));
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].column), (1, 3));
+        assert!(matches!(errors[0].kind, ErrorKind::TrailingWhitespace));
+    }
+
+    #[test]
+    fn is_error_follows_config() {
+        let overflow = FormattingError {
+            line: 1,
+            column: 1,
+            kind: ErrorKind::LineOverflow(100, 90),
+        };
+        let whitespace = FormattingError {
+            line: 1,
+            column: 1,
+            kind: ErrorKind::TrailingWhitespace,
+        };
+        let lost_comment = FormattingError {
+            line: 1,
+            column: 1,
+            kind: ErrorKind::LostComment,
+        };
+        let mut config = Config::default();
+        assert!(!overflow.is_error(&config));
+        assert!(!whitespace.is_error(&config));
+        assert!(lost_comment.is_error(&config));
+        config.set().error_on_line_overflow(true);
+        config.set().error_on_unformatted(true);
+        assert!(overflow.is_error(&config));
+        assert!(whitespace.is_error(&config));
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use ignore::gitignore;
+
+use crate::config::IgnoreList;
+
+/// Matches file paths against the gitignore-style patterns of an `IgnoreList`.
+pub struct IgnorePathSet {
+    ignore_set: gitignore::Gitignore,
+}
+
+impl IgnorePathSet {
+    pub fn from_ignore_list(ignore_list: &IgnoreList) -> Result<Self, ignore::Error> {
+        let mut ignore_builder = gitignore::GitignoreBuilder::new(ignore_list.movefmt_toml_path());
+
+        for ignore_path in ignore_list {
+            ignore_builder.add_line(None, ignore_path.to_str().unwrap())?;
+        }
+
+        Ok(IgnorePathSet {
+            ignore_set: ignore_builder.build()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ignore_set.is_empty()
+    }
+
+    /// Returns `true` if `path` or one of its parent directories matches a pattern.
+    pub fn is_match(&self, path: &Path) -> bool {
+        // The patterns are relative to the root, so they never match a path outside of it.
+        if path.has_root() && !path.starts_with(self.ignore_set.path()) {
+            return false;
+        }
+        self.ignore_set
+            .matched_path_or_any_parents(path, false)
+            .is_ignore()
+    }
+}
@@ -4,12 +4,16 @@ use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
 
-pub use crate::config::{load_config, CliOptions, Config, Edition, EmitMode, Verbosity};
+pub use crate::config::{
+    load_config, CliOptions, Config, Edition, EmitMode, NewlineStyle, Verbosity,
+};
 
 #[macro_use]
 pub mod utils;
 pub mod comment;
 pub mod config;
+pub mod format_report;
+pub mod ignore_path;
 pub mod shape;
 pub mod string;
 
@@ -38,8 +42,8 @@ pub enum ErrorKind {
     ParseError,
     /// The user mandated a version and the current version of movefmt does not
     /// satisfy that requirement.
-    #[error("version mismatch")]
-    VersionMismatch,
+    #[error("movefmt {1} does not satisfy `required_version = \"{0}\"`")]
+    VersionMismatch(String, String),
     /// If we had formatted the given node, then we would have lost a comment.
     #[error("not formatted because a comment would be lost")]
     LostComment,
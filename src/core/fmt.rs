@@ -2,6 +2,8 @@
 // Copyright (c) The BitsLab.MoveBit Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::core::hard_tabs::apply_hard_tabs;
+use crate::core::newline_style::{apply_newline_style, normalize_newlines};
 use crate::core::token_tree::*;
 use crate::syntax_fmt::branch_fmt::BranchExtractor;
 use crate::syntax_fmt::call_fmt::CallExtractor;
@@ -86,7 +88,7 @@ impl Format {
             comments_index: Default::default(),
             local_cfg: FormatConfig {
                 max_with: global_cfg.max_width(),
-                indent_size: global_cfg.indent_size(),
+                indent_size: global_cfg.indent_width(),
             },
             global_cfg,
             depth: Default::default(),
@@ -1711,7 +1713,7 @@ impl Format {
     }
 
     fn get_cur_line_len(&self) -> usize {
-        get_code_buf_len(self.last_line())
+        get_code_buf_len(self.last_line(), self.global_cfg.tab_spaces())
     }
 
     fn judge_change_new_line_when_over_limits(
@@ -1763,32 +1765,47 @@ impl Format {
 }
 
 pub fn format_entry(content: impl AsRef<str>, config: Config) -> Result<String, Diagnostics> {
+    let verbose = config.verbose() == Verbosity::Verbose;
+    let (result, timer) = format_entry_with_timer(content, config)?;
+    if verbose {
+        println!("{}", timer.phase_times());
+    }
+    Ok(result)
+}
+
+/// Same as `format_entry`, but hands the `Timer` of the run back to the caller
+/// instead of printing it, so that callers formatting several files at once
+/// can report it in a stable order.
+pub fn format_entry_with_timer(
+    content: impl AsRef<str>,
+    config: Config,
+) -> Result<(String, Timer), Diagnostics> {
     let mut timer = Timer::start();
-    let content = content.as_ref();
+    let raw_content = content.as_ref();
 
     {
         // https://github.com/movebit/movefmt/issues/2
         let mut env = CompilationEnv::new(Flags::testing(), BTreeSet::new());
-        let _ = parse_file_string(&mut env, FileHash::empty(), content)?;
+        let _ = parse_file_string(&mut env, FileHash::empty(), raw_content)?;
     }
 
-    let mut full_fmt = Format::new(
-        config.clone(),
-        content,
-        FormatContext::new(content.to_string()),
-    );
+    // Formatting works on `\n` only, the line endings are restored at the end.
+    let content = normalize_newlines(raw_content);
+    let newline_style = config.newline_style();
+    let hard_tabs = config.hard_tabs();
+    let tab_spaces = config.tab_spaces();
+    let mut full_fmt = Format::new(config, &content, FormatContext::new(content.to_string()));
 
-    full_fmt.generate_token_tree(content)?;
+    full_fmt.generate_token_tree(&content)?;
     timer = timer.done_parsing();
 
-    let result = full_fmt.format_token_trees();
-    timer = timer.done_formatting();
-    if config.verbose() == Verbosity::Verbose {
-        println!(
-            "Spent {0:.3} secs in the parsing phase, and {1:.3} secs in the formatting phase",
-            timer.get_parse_time(),
-            timer.get_format_time(),
-        );
+    let mut result = full_fmt.format_token_trees();
+    // Indentation is built with spaces, `indent_width` columns per level, so
+    // the widths computed while formatting are the same with tabs.
+    if hard_tabs {
+        result = apply_hard_tabs(&result, tab_spaces);
     }
-    Ok(result)
+    apply_newline_style(newline_style, &mut result, raw_content);
+    timer = timer.done_formatting();
+    Ok((result, timer))
 }
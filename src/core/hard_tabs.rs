@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::FileHash;
+use move_compiler::parser::lexer::{Lexer, Tok};
+use std::ops::Range;
+
+/// Replaces the leading whitespace of every line of `text` with tabs of
+/// `tab_spaces` columns, keeping spaces for the columns left over, e.g. for
+/// alignment. Lines starting inside a multi-line string literal are kept as is.
+pub fn apply_hard_tabs(text: &str, tab_spaces: usize) -> String {
+    if tab_spaces == 0 {
+        return text.to_string();
+    }
+    let string_ranges = match multi_line_string_ranges(text) {
+        Some(ranges) => ranges,
+        // Can't tell the code from the strings, so nothing is changed.
+        None => return text.to_string(),
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let in_string = string_ranges
+            .iter()
+            .any(|range| range.start < offset && offset < range.end);
+        let content = line.trim_start_matches([' ', '\t']);
+        if in_string || content.len() == line.len() {
+            result.push_str(line);
+        } else {
+            let columns = indent_columns(&line[..line.len() - content.len()], tab_spaces);
+            result.push_str(&"\t".repeat(columns / tab_spaces));
+            result.push_str(&" ".repeat(columns % tab_spaces));
+            result.push_str(content);
+        }
+        offset += line.len();
+    }
+    result
+}
+
+/// Returns the columns taken by `indent`, made of spaces and tabs.
+fn indent_columns(indent: &str, tab_spaces: usize) -> usize {
+    indent.chars().fold(0, |columns, c| {
+        if c == '\t' {
+            (columns / tab_spaces + 1) * tab_spaces
+        } else {
+            columns + 1
+        }
+    })
+}
+
+/// Returns the byte ranges of the string literals of `text` spanning several
+/// lines, or `None` if `text` doesn't lex.
+fn multi_line_string_ranges(text: &str) -> Option<Vec<Range<usize>>> {
+    let mut ranges = vec![];
+    let mut lexer = Lexer::new(text, FileHash::empty());
+    lexer.advance().ok()?;
+    while lexer.peek() != Tok::EOF {
+        if lexer.peek() == Tok::ByteStringValue && lexer.content().contains('\n') {
+            let start = lexer.start_loc();
+            ranges.push(start..start + lexer.content().len());
+        }
+        lexer.advance().ok()?;
+    }
+    Some(ranges)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn indent_with_tabs() {
+        let text =
+            "module 0x1::m {\n    fun f() {\n        let x = 1;\n      // aligned\n\n    }\n}\n";
+        assert_eq!(
+            apply_hard_tabs(text, 4),
+            "module 0x1::m {\n\tfun f() {\n\t\tlet x = 1;\n\t  // aligned\n\n\t}\n}\n"
+        );
+    }
+
+    #[test]
+    fn keep_multi_line_strings() {
+        let text = "module 0x1::m {\n    fun f(): vector<u8> {\n        b\"a\n    b\"\n    }\n}\n";
+        assert_eq!(
+            apply_hard_tabs(text, 4),
+            "module 0x1::m {\n\tfun f(): vector<u8> {\n\t\tb\"a\n    b\"\n\t}\n}\n"
+        );
+    }
+}
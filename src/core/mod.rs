@@ -1,2 +1,4 @@
 pub mod fmt;
+pub mod hard_tabs;
+pub mod newline_style;
 pub mod token_tree;
@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use commentfmt::NewlineStyle;
+use std::borrow::Cow;
+
+/// The line ending actually written, once `Auto` and `Native` are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EffectiveNewlineStyle {
+    Windows,
+    Unix,
+}
+
+/// Converts every `\r\n` of `text` to `\n`. The formatter only deals with `\n`.
+pub fn normalize_newlines(text: &str) -> Cow<str> {
+    if text.contains("\r\n") {
+        Cow::Owned(text.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Rewrites the line endings of `formatted_text`, which only contains `\n`,
+/// to `newline_style`. `Auto` follows the first line ending of `raw_input_text`.
+pub fn apply_newline_style(
+    newline_style: NewlineStyle,
+    formatted_text: &mut String,
+    raw_input_text: &str,
+) {
+    if effective_newline_style(newline_style, raw_input_text) == EffectiveNewlineStyle::Windows {
+        *formatted_text = formatted_text.replace('\n', "\r\n");
+    }
+}
+
+fn effective_newline_style(
+    newline_style: NewlineStyle,
+    raw_input_text: &str,
+) -> EffectiveNewlineStyle {
+    match newline_style {
+        NewlineStyle::Auto => match raw_input_text.find('\n') {
+            Some(pos) if raw_input_text[..pos].ends_with('\r') => EffectiveNewlineStyle::Windows,
+            Some(_) => EffectiveNewlineStyle::Unix,
+            None => native_newline_style(),
+        },
+        NewlineStyle::Native => native_newline_style(),
+        NewlineStyle::Windows => EffectiveNewlineStyle::Windows,
+        NewlineStyle::Unix => EffectiveNewlineStyle::Unix,
+    }
+}
+
+fn native_newline_style() -> EffectiveNewlineStyle {
+    if cfg!(windows) {
+        EffectiveNewlineStyle::Windows
+    } else {
+        EffectiveNewlineStyle::Unix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply(newline_style: NewlineStyle, formatted_text: &str, raw_input_text: &str) -> String {
+        let mut formatted_text = formatted_text.to_string();
+        apply_newline_style(newline_style, &mut formatted_text, raw_input_text);
+        formatted_text
+    }
+
+    #[test]
+    fn auto_follows_first_line_ending() {
+        assert_eq!(
+            apply(NewlineStyle::Auto, "a\nb\n", "a\r\nb\n"),
+            "a\r\nb\r\n"
+        );
+        assert_eq!(apply(NewlineStyle::Auto, "a\nb\n", "a\nb\r\n"), "a\nb\n");
+        assert_eq!(
+            apply(NewlineStyle::Auto, "a\n", "a"),
+            apply(NewlineStyle::Native, "a\n", "a")
+        );
+    }
+
+    #[test]
+    fn unix_and_windows_ignore_input() {
+        assert_eq!(apply(NewlineStyle::Unix, "a\nb\n", "a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(
+            apply(NewlineStyle::Windows, "a\nb\n", "a\nb\n"),
+            "a\r\nb\r\n"
+        );
+    }
+
+    #[test]
+    fn normalize_crlf() {
+        assert_eq!(normalize_newlines("a\r\nb\nc\r\n"), "a\nb\nc\n");
+        assert!(matches!(normalize_newlines("a\nb\n"), Cow::Borrowed(_)));
+    }
+}
@@ -725,7 +725,8 @@ pub(crate) fn analyze_token_tree_length(token_tree: &[TokenTree], max: usize) ->
     ret
 }
 
-pub(crate) fn get_code_buf_len(code_buffer: String) -> usize {
+/// Returns the width of `code_buffer`, where a tab counts as `tab_spaces` columns.
+pub(crate) fn get_code_buf_len(code_buffer: String, tab_spaces: usize) -> usize {
     let mut tokens_len = 0;
     let mut special_key = false;
     let mut lexer = Lexer::new(&code_buffer, FileHash::empty());
@@ -758,7 +759,7 @@ pub(crate) fn get_code_buf_len(code_buffer: String) -> usize {
             tokens_len
         }
     } else {
-        code_buffer.len()
+        code_buffer.len() + code_buffer.matches('\t').count() * tab_spaces.saturating_sub(1)
     }
 }
 
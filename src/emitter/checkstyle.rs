@@ -0,0 +1,184 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::tools::movefmt_diff::{make_diff, DiffLine, Mismatch};
+use std::fmt::{self, Display};
+
+/// Emits a checkstyle XML report, with one `<file>` element per file.
+#[derive(Debug, Default)]
+pub struct CheckstyleEmitter;
+
+impl Emitter for CheckstyleEmitter {
+    fn emit_header(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        writeln!(output, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+        write!(output, r#"<checkstyle version="4.3">"#)?;
+        Ok(())
+    }
+
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        writeln!(output, "</checkstyle>")
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        const CONTEXT_SIZE: usize = 0;
+        let diff = make_diff(original_text, formatted_text, CONTEXT_SIZE);
+        let has_diff = !diff.is_empty();
+        output_checkstyle_file(output, filename, diff)?;
+        Ok(EmitterResult { has_diff })
+    }
+
+    fn emit_parse_errors(
+        &mut self,
+        output: &mut dyn Write,
+        filename: &Path,
+        errors: &[ParseError],
+    ) -> Result<(), io::Error> {
+        write!(
+            output,
+            r#"<file name="{}">"#,
+            XmlEscaped(&filename.display().to_string())
+        )?;
+        for error in errors {
+            write!(
+                output,
+                r#"<error line="{}" column="{}" severity="error" message="{}" />"#,
+                error.line,
+                error.column,
+                XmlEscaped(&error.message)
+            )?;
+        }
+        write!(output, "</file>")?;
+        Ok(())
+    }
+}
+
+/// Writes one warning per line the formatter would change, located in the
+/// original file.
+fn output_checkstyle_file<T>(
+    mut writer: T,
+    filename: &Path,
+    diff: Vec<Mismatch>,
+) -> Result<(), io::Error>
+where
+    T: Write,
+{
+    write!(
+        writer,
+        r#"<file name="{}">"#,
+        XmlEscaped(&filename.display().to_string())
+    )?;
+    for mismatch in diff {
+        let begin_line = mismatch.line_number_orig;
+        let mut line_counter = 0;
+        for line in mismatch.lines {
+            // Do nothing with `DiffLine::Context` and `DiffLine::Resulting`.
+            if let DiffLine::Expected(message) = line {
+                write!(
+                    writer,
+                    r#"<error line="{}" severity="warning" message="Should be `{}`" />"#,
+                    begin_line + line_counter,
+                    XmlEscaped(&message)
+                )?;
+                line_counter += 1;
+            }
+        }
+    }
+    write!(writer, "</file>")?;
+    Ok(())
+}
+
+/// Convert special characters into XML entities.
+/// This is needed for checkstyle output.
+struct XmlEscaped<'a>(&'a str);
+
+impl<'a> Display for XmlEscaped<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for char in self.0.chars() {
+            match char {
+                '<' => write!(formatter, "&lt;"),
+                '>' => write!(formatter, "&gt;"),
+                '"' => write!(formatter, "&quot;"),
+                '\'' => write!(formatter, "&apos;"),
+                '&' => write!(formatter, "&amp;"),
+                _ => write!(formatter, "{}", char),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_mismatches_and_parse_errors() {
+        let mut emitter = CheckstyleEmitter;
+        let mut writer = Vec::new();
+        emitter.emit_header(&mut writer).unwrap();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: Path::new("sources/a.move"),
+                    original_text: "module 0x1::a {\nfun f(x: &vector<u8>) {  }\n}\n",
+                    formatted_text: "module 0x1::a {\n    fun f(x: &vector<u8>) {}\n}\n",
+                },
+            )
+            .unwrap();
+        emitter
+            .emit_parse_errors(
+                &mut writer,
+                Path::new("sources/b&c.move"),
+                &[ParseError {
+                    line: 3,
+                    column: 5,
+                    message: "unexpected '>', expected \"}\"".to_string(),
+                }],
+            )
+            .unwrap();
+        emitter.emit_footer(&mut writer).unwrap();
+
+        assert!(result.has_diff);
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<checkstyle version=\"4.3\">\
+             <file name=\"sources/a.move\"><error line=\"2\" severity=\"warning\" \
+             message=\"Should be `    fun f(x: &amp;vector&lt;u8&gt;) {}`\" /></file>\
+             <file name=\"sources/b&amp;c.move\"><error line=\"3\" column=\"5\" \
+             severity=\"error\" message=\"unexpected &apos;&gt;&apos;, expected &quot;}&quot;\" />\
+             </file></checkstyle>\n"
+        );
+    }
+
+    #[test]
+    fn emits_empty_file_element_without_diff() {
+        let mut writer = Vec::new();
+        let result = CheckstyleEmitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: Path::new("a.move"),
+                    original_text: "a\n",
+                    formatted_text: "a\n",
+                },
+            )
+            .unwrap();
+        assert!(!result.has_diff);
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "<file name=\"a.move\"></file>"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::tools::movefmt_diff::{make_diff, DiffLine, Mismatch};
+use serde::Serialize;
+use serde_json::to_string as to_json_string;
+
+/// Emits the mismatches of every file as a single JSON array, using the
+/// same schema as rustfmt's `--emit json`.
+#[derive(Debug, Default)]
+pub struct JsonEmitter {
+    mismatched_files: Vec<MismatchedFile>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct MismatchedBlock {
+    original_begin_line: u32,
+    original_end_line: u32,
+    expected_begin_line: u32,
+    expected_end_line: u32,
+    original: String,
+    expected: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct MismatchedFile {
+    file: String,
+    mismatches: Vec<MismatchedBlock>,
+}
+
+impl Emitter for JsonEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        writeln!(output, "{}", &to_json_string(&self.mismatched_files)?)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        const CONTEXT_SIZE: usize = 0;
+        let diff = make_diff(original_text, formatted_text, CONTEXT_SIZE);
+        let has_diff = !diff.is_empty();
+
+        if has_diff {
+            self.add_misformatted_file(filename, diff);
+        }
+
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+impl JsonEmitter {
+    fn add_misformatted_file(&mut self, filename: &Path, diff: Vec<Mismatch>) {
+        let mut mismatches = vec![];
+        for mismatch in diff {
+            let original_begin_line = mismatch.line_number_orig;
+            let expected_begin_line = mismatch.line_number;
+            let mut original_end_line = original_begin_line;
+            let mut expected_end_line = expected_begin_line;
+            let mut original_line_counter = 0;
+            let mut expected_line_counter = 0;
+            let mut original = String::new();
+            let mut expected = String::new();
+
+            for line in mismatch.lines {
+                match line {
+                    DiffLine::Expected(msg) => {
+                        expected_end_line = expected_begin_line + expected_line_counter;
+                        expected_line_counter += 1;
+                        expected.push_str(&msg);
+                        expected.push('\n');
+                    }
+                    DiffLine::Resulting(msg) => {
+                        original_end_line = original_begin_line + original_line_counter;
+                        original_line_counter += 1;
+                        original.push_str(&msg);
+                        original.push('\n');
+                    }
+                    DiffLine::Context(_) => continue,
+                }
+            }
+
+            mismatches.push(MismatchedBlock {
+                original_begin_line,
+                original_end_line,
+                expected_begin_line,
+                expected_end_line,
+                original,
+                expected,
+            });
+        }
+        self.mismatched_files.push(MismatchedFile {
+            file: filename.display().to_string(),
+            mismatches,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_array_of_mismatched_files() {
+        let mut emitter = JsonEmitter::default();
+        let mut writer = Vec::new();
+        let result = emitter
+            .emit_formatted_file(
+                &mut writer,
+                FormattedFile {
+                    filename: Path::new("sources/a.move"),
+                    original_text: "module 0x1::a {\nfun f() {  }\n}\n",
+                    formatted_text: "module 0x1::a {\n    fun f() {}\n}\n",
+                },
+            )
+            .unwrap();
+        emitter.emit_footer(&mut writer).unwrap();
+
+        assert!(result.has_diff);
+        assert_eq!(
+            String::from_utf8(writer).unwrap(),
+            "[{\"file\":\"sources/a.move\",\"mismatches\":[{\"original_begin_line\":2,\
+             \"original_end_line\":2,\"expected_begin_line\":2,\"expected_end_line\":2,\
+             \"original\":\"fun f() {  }\\n\",\"expected\":\"    fun f() {}\\n\"}]}]\n"
+        );
+    }
+}
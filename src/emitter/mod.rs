@@ -0,0 +1,90 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+pub use self::checkstyle::*;
+pub use self::json::*;
+pub use self::patch::*;
+pub use self::sarif::*;
+use crate::tools::utils::FileLineMappingOneFile;
+use move_compiler::diagnostics::Diagnostics;
+use std::io::{self, Write};
+use std::path::Path;
+
+mod checkstyle;
+mod json;
+mod patch;
+mod sarif;
+
+/// A file formatted successfully, ready to be emitted.
+pub struct FormattedFile<'a> {
+    pub filename: &'a Path,
+    pub original_text: &'a str,
+    pub formatted_text: &'a str,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EmitterResult {
+    pub has_diff: bool,
+}
+
+/// A parse diagnostic of a file that could not be formatted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the primary label.
+    pub line: u32,
+    /// 1-based column of the primary label.
+    pub column: u32,
+    pub message: String,
+}
+
+/// Locates each diagnostic reported while parsing `content`.
+pub fn parse_errors(content: &str, diags: Diagnostics) -> Vec<ParseError> {
+    let mut line_mapping = FileLineMappingOneFile::default();
+    line_mapping.update(content);
+    diags
+        .into_codespan_format()
+        .into_iter()
+        .map(|(_, msg, (loc, label), _, _)| {
+            let (line, column) = line_mapping
+                .translate(loc.start(), loc.end())
+                .map(|range| (range.start.line + 1, range.start.character + 1))
+                .unwrap_or((1, 1));
+            ParseError {
+                line,
+                column,
+                message: format!("{}: {}", msg, label),
+            }
+        })
+        .collect()
+}
+
+/// Renders the result of a whole run in one machine readable format.
+///
+/// The header is written before the first file and the footer after the
+/// last one, so emitters producing a single document can buffer the files.
+pub trait Emitter {
+    fn emit_formatted_file(
+        &mut self,
+        output: &mut dyn Write,
+        formatted_file: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error>;
+
+    /// Reports a file skipped because it could not be parsed.
+    fn emit_parse_errors(
+        &mut self,
+        _output: &mut dyn Write,
+        _filename: &Path,
+        _errors: &[ParseError],
+    ) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn emit_header(&self, _output: &mut dyn Write) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn emit_footer(&self, _output: &mut dyn Write) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::path::PathBuf;
+
+/// Emits a unified diff that `patch -p1` and `git apply` accept.
+#[derive(Debug)]
+pub struct PatchEmitter {
+    context_size: usize,
+    /// File names are written relative to this directory.
+    base_dir: PathBuf,
+}
+
+impl PatchEmitter {
+    pub fn new(context_size: usize, base_dir: PathBuf) -> Self {
+        PatchEmitter {
+            context_size,
+            base_dir,
+        }
+    }
+}
+
+impl Emitter for PatchEmitter {
+    fn emit_formatted_file(
+        &mut self,
+        output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        let (old_name, new_name) = match filename.strip_prefix(&self.base_dir) {
+            Ok(name) => {
+                let name = name
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (format!("a/{}", name), format!("b/{}", name))
+            }
+            // A file outside of `base_dir` keeps its own path, e.g. an absolute one.
+            Err(_) => {
+                let name = filename.display().to_string();
+                (name.clone(), name)
+            }
+        };
+        let has_diff = write_unified_diff(
+            output,
+            &old_name,
+            &new_name,
+            original_text,
+            formatted_text,
+            self.context_size,
+        )?;
+        Ok(EmitterResult { has_diff })
+    }
+}
+
+/// Writes the unified diff turning `original` into `formatted`, returning
+/// `false` without writing anything if they are equal.
+fn write_unified_diff(
+    output: &mut dyn Write,
+    old_name: &str,
+    new_name: &str,
+    original: &str,
+    formatted: &str,
+    context_size: usize,
+) -> Result<bool, io::Error> {
+    // Keep the line terminators, so a missing newline at the end of file is a change too.
+    let original: Vec<_> = original.split_inclusive('\n').collect();
+    let formatted: Vec<_> = formatted.split_inclusive('\n').collect();
+    let lines = diff::slice(&original, &formatted);
+    let changes: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, diff::Result::Both(..)))
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return Ok(false);
+    }
+
+    writeln!(output, "--- {}", old_name)?;
+    writeln!(output, "+++ {}", new_name)?;
+
+    // Changes closer than twice the context size share a hunk.
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for index in changes {
+        match hunks.last_mut() {
+            Some((_, last)) if index - *last <= 2 * context_size + 1 => *last = index,
+            _ => hunks.push((index, index)),
+        }
+    }
+
+    for (first, last) in hunks {
+        let begin = first.saturating_sub(context_size);
+        let end = (last + context_size + 1).min(lines.len());
+        let count_before = |take_original: bool| {
+            lines[..begin]
+                .iter()
+                .filter(|line| match line {
+                    diff::Result::Both(..) => true,
+                    diff::Result::Left(_) => take_original,
+                    diff::Result::Right(_) => !take_original,
+                })
+                .count()
+        };
+        let hunk = &lines[begin..end];
+        let original_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, diff::Result::Right(_)))
+            .count();
+        let formatted_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, diff::Result::Left(_)))
+            .count();
+        writeln!(
+            output,
+            "@@ -{} +{} @@",
+            hunk_range(count_before(true), original_count),
+            hunk_range(count_before(false), formatted_count),
+        )?;
+
+        for line in hunk {
+            let (prefix, text) = match line {
+                diff::Result::Both(text, _) => (' ', text),
+                diff::Result::Left(text) => ('-', text),
+                diff::Result::Right(text) => ('+', text),
+            };
+            write!(output, "{}{}", prefix, text)?;
+            if !text.ends_with('\n') {
+                writeln!(output)?;
+                writeln!(output, "\\ No newline at end of file")?;
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Formats the `start,count` range of a hunk header. An empty range starts
+/// at the line before it, as in the output of GNU diff.
+fn hunk_range(lines_before: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", lines_before)
+    } else {
+        format!("{},{}", lines_before + 1, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(original: &str, formatted: &str, context_size: usize) -> String {
+        let mut output = Vec::new();
+        write_unified_diff(
+            &mut output,
+            "a/a.move",
+            "b/a.move",
+            original,
+            formatted,
+            context_size,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn writes_headers_and_hunks() {
+        assert_eq!(
+            patch("a\nb\nc\nd\n", "a\nB\nc\nd\n", 1),
+            "--- a/a.move\n+++ b/a.move\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+        assert_eq!(patch("a\n", "a\n", 3), "");
+    }
+
+    #[test]
+    fn marks_missing_newline_at_end_of_file() {
+        assert_eq!(
+            patch("a\nb", "a\nb\n", 0),
+            "--- a/a.move\n+++ b/a.move\n@@ -2,1 +2,1 @@\n-b\n\\ No newline at end of file\n+b\n"
+        );
+    }
+
+    #[test]
+    fn names_files_relative_to_base_dir() {
+        let mut emitter = PatchEmitter::new(0, PathBuf::from("/base"));
+        let mut output = Vec::new();
+        for filename in ["/base/sources/a.move", "/other/a.move"] {
+            emitter
+                .emit_formatted_file(
+                    &mut output,
+                    FormattedFile {
+                        filename: Path::new(filename),
+                        original_text: "a\n",
+                        formatted_text: "b\n",
+                    },
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "--- a/sources/a.move\n+++ b/sources/a.move\n@@ -1,1 +1,1 @@\n-a\n+b\n\
+             --- /other/a.move\n+++ /other/a.move\n@@ -1,1 +1,1 @@\n-a\n+b\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::tools::movefmt_diff::{make_diff, DiffLine};
+use serde_json::{json, Value};
+
+const FORMAT_RULE_ID: &str = "movefmt/unformatted";
+const PARSE_RULE_ID: &str = "movefmt/parse-error";
+
+/// Emits a SARIF 2.1.0 log with a single run holding the results of all files.
+#[derive(Debug, Default)]
+pub struct SarifEmitter {
+    results: Vec<Value>,
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_footer(&self, output: &mut dyn Write) -> Result<(), io::Error> {
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "movefmt",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/movebit/movefmt",
+                        "rules": [
+                            {
+                                "id": FORMAT_RULE_ID,
+                                "shortDescription": { "text": "Code is not formatted" },
+                            },
+                            {
+                                "id": PARSE_RULE_ID,
+                                "shortDescription": { "text": "File could not be parsed" },
+                            },
+                        ],
+                    },
+                },
+                "results": self.results,
+            }],
+        });
+        writeln!(output, "{}", serde_json::to_string_pretty(&log)?)
+    }
+
+    fn emit_formatted_file(
+        &mut self,
+        _output: &mut dyn Write,
+        FormattedFile {
+            filename,
+            original_text,
+            formatted_text,
+        }: FormattedFile<'_>,
+    ) -> Result<EmitterResult, io::Error> {
+        const CONTEXT_SIZE: usize = 0;
+        let diff = make_diff(original_text, formatted_text, CONTEXT_SIZE);
+        let has_diff = !diff.is_empty();
+
+        for mismatch in diff {
+            let start_line = mismatch.line_number_orig;
+            let mut lines_removed = 0;
+            let mut expected = String::new();
+            for line in mismatch.lines {
+                match line {
+                    DiffLine::Expected(line) => {
+                        expected.push_str(&line);
+                        expected.push('\n');
+                    }
+                    DiffLine::Resulting(_) => lines_removed += 1,
+                    DiffLine::Context(_) => {}
+                }
+            }
+            let end_line = start_line + lines_removed.max(1) - 1;
+            self.results.push(result(
+                FORMAT_RULE_ID,
+                "warning",
+                format!("Code is not formatted, it should be:\n{}", expected),
+                filename,
+                json!({ "startLine": start_line, "endLine": end_line }),
+            ));
+        }
+
+        Ok(EmitterResult { has_diff })
+    }
+
+    fn emit_parse_errors(
+        &mut self,
+        _output: &mut dyn Write,
+        filename: &Path,
+        errors: &[ParseError],
+    ) -> Result<(), io::Error> {
+        for error in errors {
+            self.results.push(result(
+                PARSE_RULE_ID,
+                "error",
+                error.message.clone(),
+                filename,
+                json!({ "startLine": error.line, "startColumn": error.column }),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn result(rule_id: &str, level: &str, message: String, filename: &Path, region: Value) -> Value {
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": artifact_uri(filename) },
+                "region": region,
+            },
+        }],
+    })
+}
+
+fn artifact_uri(filename: &Path) -> String {
+    url::Url::from_file_path(filename)
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| filename.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sarif_results(files: &[(&str, &str, &str)]) -> Vec<Value> {
+        let mut emitter = SarifEmitter::default();
+        let mut writer = Vec::new();
+        for (filename, original_text, formatted_text) in files {
+            emitter
+                .emit_formatted_file(
+                    &mut writer,
+                    FormattedFile {
+                        filename: Path::new(filename),
+                        original_text,
+                        formatted_text,
+                    },
+                )
+                .unwrap();
+        }
+        // Nothing is written before the footer.
+        assert!(writer.is_empty());
+        emitter.emit_footer(&mut writer).unwrap();
+        let log: Value = serde_json::from_slice(&writer).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        log["runs"][0]["results"].as_array().unwrap().clone()
+    }
+
+    fn region(result: &Value) -> (&Value, &Value) {
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        (&region["startLine"], &region["endLine"])
+    }
+
+    #[test]
+    fn regions_of_insertion_and_deletion() {
+        let results = sarif_results(&[
+            ("a.move", "a\nc\n", "a\nb\nc\n"),
+            ("b.move", "a\nb\nc\nd\n", "a\nd\n"),
+        ]);
+        assert_eq!(results.len(), 2);
+        // An insertion points at the line it's inserted before.
+        assert_eq!(region(&results[0]), (&json!(2), &json!(2)));
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Code is not formatted, it should be:\nb\n"
+        );
+        // A deletion spans the removed lines.
+        assert_eq!(region(&results[1]), (&json!(2), &json!(3)));
+        assert_eq!(results[1]["ruleId"], FORMAT_RULE_ID);
+    }
+
+    #[test]
+    fn parse_errors_and_artifact_uri() {
+        let mut emitter = SarifEmitter::default();
+        let mut writer = Vec::new();
+        emitter
+            .emit_parse_errors(
+                &mut writer,
+                Path::new("/tmp/a.move"),
+                &[ParseError {
+                    line: 3,
+                    column: 5,
+                    message: "unexpected token".to_string(),
+                }],
+            )
+            .unwrap();
+        emitter.emit_footer(&mut writer).unwrap();
+        let log: Value = serde_json::from_slice(&writer).unwrap();
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], PARSE_RULE_ID);
+        assert_eq!(result["level"], "error");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "file:///tmp/a.move");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 5);
+
+        // A relative path can't be a file URL, so it's kept as is.
+        assert_eq!(artifact_uri(Path::new("sources/a.move")), "sources/a.move");
+    }
+}
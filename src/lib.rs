@@ -35,5 +35,6 @@ macro_rules! impl_convert_loc {
 extern crate move_ir_types;
 
 pub mod core;
+pub mod emitter;
 pub mod syntax_fmt;
 pub mod tools;
@@ -20,6 +20,11 @@ pub struct BigBlockExtractor {
 
 impl BigBlockExtractor {
     pub fn new(fmt_buffer: String) -> Self {
+        Self::try_new(fmt_buffer).unwrap()
+    }
+
+    /// Like `new`, but returns `None` if `fmt_buffer` doesn't parse.
+    pub fn try_new(fmt_buffer: String) -> Option<Self> {
         let mut big_block_extractor = Self {
             blk_loc_vec: vec![],
             line_mapping: FileLineMappingOneFile::default(),
@@ -28,12 +33,12 @@ impl BigBlockExtractor {
         big_block_extractor.line_mapping.update(&fmt_buffer);
         let attrs: BTreeSet<String> = BTreeSet::new();
         let mut env = CompilationEnv::new(Flags::testing(), attrs);
-        let (defs, _) = parse_file_string(&mut env, FileHash::empty(), &fmt_buffer).unwrap();
+        let (defs, _) = parse_file_string(&mut env, FileHash::empty(), &fmt_buffer).ok()?;
 
         for d in defs.iter() {
             big_block_extractor.collect_definition(d);
         }
-        big_block_extractor
+        Some(big_block_extractor)
     }
 
     fn collect_struct(&mut self, s: &StructDefinition) {
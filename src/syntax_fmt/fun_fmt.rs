@@ -411,7 +411,7 @@ pub(crate) fn process_block_comment_before_fun_header(
         if lexer.peek() != Tok::EOF && !fun_header_str[0..lexer.start_loc()].trim_start().is_empty()
         {
             let mut insert_str = "\n".to_string();
-            insert_str.push_str(" ".to_string().repeat(config.indent_size()).as_str());
+            insert_str.push_str(" ".to_string().repeat(config.indent_width()).as_str());
             result.insert_str(
                 fun_extractor.loc_vec[fun_idx].start() as usize + insert_char_nums,
                 &insert_str,
@@ -486,7 +486,7 @@ pub(crate) fn process_fun_header_too_long(fmt_buffer: String, config: Config) ->
             if let Some(indent) = fun_header_str.find(trimed_header_prefix) {
                 insert_str.push_str(
                     " ".to_string()
-                        .repeat(indent + config.indent_size())
+                        .repeat(indent + config.indent_width())
                         .as_str(),
                 );
             }
@@ -540,9 +540,9 @@ pub(crate) fn process_fun_ret_ty(fmt_buffer: String, config: Config) -> String {
                 // tracing::debug!("fun_header_str = \n{:?}", &buf[0..(ret_ty_loc.start() as usize - ret_ty_str.len())]);
                 result.insert_str(
                     ret_ty_loc.start() as usize - ret_ty_str.len() + insert_char_nums,
-                    " ".to_string().repeat(config.indent_size()).as_str(),
+                    " ".to_string().repeat(config.indent_width()).as_str(),
                 );
-                insert_char_nums += config.indent_size();
+                insert_char_nums += config.indent_width();
             }
         }
     }
@@ -297,7 +297,7 @@ pub fn process_block_comment_before_spec_header(fmt_buffer: String, config: Conf
         if lexer.peek() != Tok::EOF && !fun_header_str[0..lexer.start_loc()].trim_start().is_empty()
         {
             let mut insert_str = "\n".to_string();
-            insert_str.push_str(" ".to_string().repeat(config.indent_size()).as_str());
+            insert_str.push_str(" ".to_string().repeat(config.indent_width()).as_str());
             result.insert_str(
                 spec_extractor.spec_fn_loc_vec[fun_idx].start() as usize + insert_char_nums,
                 &insert_str,
@@ -383,7 +383,7 @@ pub fn process_spec_fn_header_too_long(fmt_buffer: String, config: Config) -> St
             if let Some(indent) = fun_header_str.find(trimed_header_prefix) {
                 insert_str.push_str(
                     " ".to_string()
-                        .repeat(indent + config.indent_size())
+                        .repeat(indent + config.indent_width())
                         .as_str(),
                 );
             }
@@ -420,7 +420,7 @@ pub fn process_pragma(fmt_buffer: String, config: Config) -> String {
             let mut insert_str = "\n".to_string();
             insert_str.push_str(
                 " ".to_string()
-                    .repeat(config.indent_size() + leading_space_cnt)
+                    .repeat(config.indent_width() + leading_space_cnt)
                     .as_str(),
             );
 
@@ -162,7 +162,7 @@ pub fn optimize_brace_of_use(fmt_buffer: String, config: Config) -> String {
                                 if let Some(indent) = use_module_str.find(trimed_header_prefix) {
                                     insert_str.push_str(
                                         " ".to_string()
-                                            .repeat(indent + config.indent_size())
+                                            .repeat(indent + config.indent_width())
                                             .as_str(),
                                     );
                                 }
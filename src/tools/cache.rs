@@ -0,0 +1,162 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use commentfmt::Config;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Default name of the file recording the files known to be formatted.
+pub const CACHE_FILE_NAME: &str = ".movefmt-cache";
+
+/// Remembers the files that were already formatted, so unchanged files can be
+/// skipped on the next run.
+///
+/// Each file is recorded with a key hashing its content, the movefmt version
+/// and the config it was formatted with, so changing any of them is a miss.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FormatCache {
+    version: String,
+    files: BTreeMap<PathBuf, String>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl FormatCache {
+    /// Reads the cache at `path`. A missing or unreadable cache, or one
+    /// written by another version of movefmt, is an empty cache.
+    pub fn load(path: &Path) -> FormatCache {
+        let empty = FormatCache {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..Default::default()
+        };
+        let cache = match fs::read(path) {
+            Ok(content) => serde_json::from_slice::<FormatCache>(&content),
+            Err(_) => return empty,
+        };
+        match cache {
+            Ok(cache) if cache.version == empty.version => cache,
+            Ok(_) => empty,
+            Err(err) => {
+                tracing::warn!("ignoring invalid cache {}: {}", path.display(), err);
+                empty
+            }
+        }
+    }
+
+    /// Computes the key of `content` formatted with `config`.
+    ///
+    /// `emit_mode` and `verbose` only change what is done with the output, and
+    /// are set from the command line, so they're left out of the key.
+    pub fn key(content: &str, config: &Config) -> String {
+        let mut options = config.all_options();
+        options.emit_mode = None;
+        options.verbose = None;
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update([0]);
+        hasher.update(options.to_toml().unwrap_or_default());
+        hasher.update([0]);
+        hasher.update(content);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Returns `true` if `file` was formatted when its key was `key`.
+    pub fn is_formatted(&self, file: &Path, key: &str) -> bool {
+        self.files.get(file).map_or(false, |k| k == key)
+    }
+
+    /// Records that `file` is formatted with key `key`.
+    pub fn insert(&mut self, file: PathBuf, key: String) {
+        if !self.is_formatted(&file, &key) {
+            self.files.insert(file, key);
+            self.dirty = true;
+        }
+    }
+
+    /// Writes the cache to `path` if anything was recorded since it was loaded.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use commentfmt::{EmitMode, Verbosity};
+    use std::env;
+
+    fn temp_cache_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("movefmt-cache-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = temp_cache_path("load");
+        assert!(FormatCache::load(&path).files.is_empty());
+
+        let mut cache = FormatCache::load(&path);
+        let key = FormatCache::key("module 0x1::m {}\n", &Config::default());
+        cache.insert(PathBuf::from("a.move"), key.clone());
+        cache.save(&path).unwrap();
+        let cache = FormatCache::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(cache.is_formatted(Path::new("a.move"), &key));
+        assert!(!cache.is_formatted(Path::new("a.move"), "other"));
+        assert!(!cache.is_formatted(Path::new("b.move"), &key));
+    }
+
+    #[test]
+    fn other_version_is_empty() {
+        let path = temp_cache_path("version");
+        fs::write(&path, r#"{"version":"0.0.0","files":{"a.move":"key"}}"#).unwrap();
+        let cache = FormatCache::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(!cache.is_formatted(Path::new("a.move"), "key"));
+        assert_eq!(cache.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn key_ignores_output_options() {
+        let content = "module 0x1::m {}\n";
+        let config = Config::default();
+        let key = FormatCache::key(content, &config);
+        assert_eq!(key, FormatCache::key(content, &config));
+
+        let mut check_config = Config::default();
+        check_config.set().emit_mode(EmitMode::Diff);
+        check_config.set().verbose(Verbosity::Verbose);
+        assert_eq!(key, FormatCache::key(content, &check_config));
+
+        let mut tab_config = Config::default();
+        tab_config.set().tab_spaces(2);
+        assert_ne!(key, FormatCache::key(content, &tab_config));
+        assert_ne!(key, FormatCache::key("module 0x1::n {}\n", &config));
+    }
+
+    #[test]
+    fn save_only_when_dirty() {
+        let path = temp_cache_path("dirty");
+        let mut cache = FormatCache::load(&path);
+        cache.save(&path).unwrap();
+        assert!(!path.exists());
+
+        cache.insert(PathBuf::from("a.move"), "key".to_string());
+        cache.save(&path).unwrap();
+        assert!(path.exists());
+
+        // Recording the same key again changes nothing.
+        let mut cache = FormatCache::load(&path);
+        fs::remove_file(&path).unwrap();
+        cache.insert(PathBuf::from("a.move"), "key".to_string());
+        cache.save(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
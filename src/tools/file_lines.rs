@@ -0,0 +1,339 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::syntax_fmt::big_block_fmt::BigBlockExtractor;
+use anyhow::{format_err, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A closed range of 1-based line numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+impl LineRange {
+    pub fn contains(&self, line: usize) -> bool {
+        self.lo <= line && line <= self.hi
+    }
+}
+
+/// The lines to format in each file, as given by `--file-lines`.
+/// Files without an entry are left untouched.
+#[derive(Clone, Debug, Default)]
+pub struct FileLines {
+    ranges: HashMap<PathBuf, Vec<LineRange>>,
+}
+
+#[derive(Deserialize)]
+struct JsonSpan {
+    file: PathBuf,
+    range: (usize, usize),
+}
+
+impl FileLines {
+    /// Parses `[{"file":"a.move","range":[10,40]}, ...]`. Relative file names
+    /// are resolved against `base`.
+    pub fn from_json(json: &str, base: &Path) -> Result<FileLines> {
+        let spans: Vec<JsonSpan> = serde_json::from_str(json)
+            .map_err(|err| format_err!("invalid `--file-lines` value: {}", err))?;
+        let mut ranges: HashMap<PathBuf, Vec<LineRange>> = HashMap::new();
+        for JsonSpan {
+            file,
+            range: (lo, hi),
+        } in spans
+        {
+            if lo == 0 || lo > hi {
+                return Err(format_err!(
+                    "invalid line range [{}, {}] for {}",
+                    lo,
+                    hi,
+                    file.display()
+                ));
+            }
+            ranges
+                .entry(canonical_path(&base.join(file)))
+                .or_default()
+                .push(LineRange { lo, hi });
+        }
+        Ok(FileLines { ranges })
+    }
+
+    /// Collects the lines changed in the working tree of the git repository
+    /// containing `dir` relative to `rev`, by running `git diff -U0 <rev>`.
+    /// Deleted lines are recorded as the lines around them.
+    pub fn from_git_diff(rev: &str, dir: &Path) -> Result<FileLines> {
+        let root = run_git(dir, &["rev-parse", "--show-toplevel"])?;
+        let diff = run_git(
+            dir,
+            &[
+                "diff",
+                "-U0",
+                "--no-color",
+                "--no-ext-diff",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                rev,
+                "--",
+                "*.move",
+            ],
+        )?;
+        Ok(parse_unified_diff(&diff, Path::new(root.trim_end())))
+    }
+
+    /// Returns the files that have line ranges.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<_> = self.ranges.keys().cloned().collect();
+        files.sort();
+        files
+    }
+
+    /// Returns the ranges to format in `file`, empty if it has none.
+    pub fn ranges_of(&self, file: &Path) -> &[LineRange] {
+        self.ranges
+            .get(&canonical_path(file))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| format_err!("failed to run git: {}", err))?;
+    if !output.status.success() {
+        return Err(format_err!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Collects the line ranges of the new version of each file from the hunk
+/// headers of a unified diff, with file names relative to `root`.
+fn parse_unified_diff(diff: &str, root: &Path) -> FileLines {
+    let mut ranges: HashMap<PathBuf, Vec<LineRange>> = HashMap::new();
+    let mut file = None;
+    let mut prev_line = "";
+    for line in diff.lines() {
+        match line.strip_prefix("+++ ") {
+            Some(name) if prev_line.starts_with("--- ") => {
+                // A deleted file has no new version.
+                file = name
+                    .strip_prefix("b/")
+                    .map(|name| canonical_path(&root.join(name)));
+            }
+            _ => {
+                if let (Some(file), Some(range)) = (&file, parse_hunk_header(line)) {
+                    ranges.entry(file.clone()).or_default().push(range);
+                }
+            }
+        }
+        prev_line = line;
+    }
+    FileLines { ranges }
+}
+
+/// Parses the range of the new version from a `@@ -l,s +l,s @@` hunk header,
+/// where a count of 1 may be omitted.
+fn parse_hunk_header(line: &str) -> Option<LineRange> {
+    let new_range = line
+        .strip_prefix("@@ ")?
+        .split_whitespace()
+        .find_map(|range| range.strip_prefix('+'))?;
+    let (start, count) = match new_range.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (new_range.parse::<usize>().ok()?, 1),
+    };
+    if count == 0 {
+        // Lines were deleted after line `start`.
+        Some(LineRange {
+            lo: start.max(1),
+            hi: start + 1,
+        })
+    } else {
+        Some(LineRange {
+            lo: start,
+            hi: start + count - 1,
+        })
+    }
+}
+
+/// Widens each of `ranges` to the top-level items (functions, structs and
+/// specs) of `content` it overlaps. Ranges outside of any item are dropped,
+/// and so are all of them if `content` doesn't parse.
+pub fn expand_to_items(content: &str, ranges: &[LineRange]) -> Vec<LineRange> {
+    if ranges.is_empty() {
+        return vec![];
+    }
+    let extractor = match BigBlockExtractor::try_new(content.to_string()) {
+        Some(extractor) => extractor,
+        None => return vec![],
+    };
+    extractor
+        .blk_loc_vec
+        .iter()
+        .filter_map(|loc| {
+            let lines = extractor.line_mapping.translate(loc.start(), loc.end())?;
+            // The mapping is 0-based.
+            Some(LineRange {
+                lo: lines.start.line as usize + 1,
+                hi: lines.end.line as usize + 1,
+            })
+        })
+        .filter(|item| {
+            ranges
+                .iter()
+                .any(|range| range.lo <= item.hi && item.lo <= range.hi)
+        })
+        .collect()
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Applies the changes from `original` to `formatted` that only touch the
+/// lines of `original` within `ranges`, so every line outside of them is kept
+/// byte for byte.
+///
+/// A block of changed lines is applied line by line when it only reindents
+/// the lines. Otherwise it is applied only if all the lines it removes, or the
+/// lines around the place it inserts into, are within the ranges.
+pub fn format_line_ranges(original: &str, formatted: &str, ranges: &[LineRange]) -> String {
+    // The terminators are part of the lines, so adding the final newline changes the
+    // last line, which is then kept as is unless it's within the ranges.
+    let original_lines: Vec<_> = original.split_inclusive('\n').collect();
+    let formatted_lines: Vec<_> = formatted.split_inclusive('\n').collect();
+
+    let mut result = String::with_capacity(original.len());
+    // The 1-based line number in `original` of the next removed or kept line.
+    let mut line = 1;
+    let mut removed = vec![];
+    let mut added = vec![];
+    for diff_line in diff::slice(&original_lines, &formatted_lines) {
+        match diff_line {
+            diff::Result::Left(text) => {
+                removed.push(*text);
+                line += 1;
+            }
+            diff::Result::Right(text) => added.push(*text),
+            diff::Result::Both(text, _) => {
+                apply_block(&mut result, line, &mut removed, &mut added, ranges);
+                result.push_str(text);
+                line += 1;
+            }
+        }
+    }
+    apply_block(&mut result, line, &mut removed, &mut added, ranges);
+    result
+}
+
+/// Pushes either the `added` or the `removed` lines of the block of changes
+/// ending before line `line` of the original text.
+fn apply_block<'a>(
+    result: &mut String,
+    line: usize,
+    removed: &mut Vec<&'a str>,
+    added: &mut Vec<&'a str>,
+    ranges: &[LineRange],
+) {
+    let in_ranges = |line: usize| ranges.iter().any(|range| range.contains(line));
+    let first = line - removed.len();
+    let reindented = removed.len() == added.len()
+        && removed
+            .iter()
+            .zip(added.iter())
+            .all(|(old, new)| old.trim() == new.trim());
+    if reindented {
+        for (offset, (old, new)) in removed.iter().zip(added.iter()).enumerate() {
+            result.push_str(if in_ranges(first + offset) { new } else { old });
+        }
+    } else {
+        let applied = if removed.is_empty() {
+            in_ranges(first - 1) || in_ranges(first)
+        } else {
+            (first..line).all(in_ranges)
+        };
+        let kept = if applied { &added } else { &removed };
+        for text in kept.iter() {
+            result.push_str(text);
+        }
+    }
+    removed.clear();
+    added.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_hunk_headers_of_new_files() {
+        let diff = "diff --git a/a.move b/a.move\n\
+                    --- a/a.move\n\
+                    +++ b/a.move\n\
+                    @@ -3 +3 @@ module a {\n\
+                    -x\n\
+                    +y\n\
+                    @@ -10,2 +9,0 @@\n\
+                    -z\n\
+                    -z\n\
+                    @@ -20,0 +19,3 @@\n\
+                    +w\n\
+                    diff --git a/b.move b/b.move\n\
+                    --- a/b.move\n\
+                    +++ /dev/null\n\
+                    @@ -1 +0,0 @@\n\
+                    -v\n";
+        let root = Path::new("/no/such/root");
+        let file_lines = parse_unified_diff(diff, root);
+        assert_eq!(file_lines.files(), vec![root.join("a.move")]);
+        assert_eq!(
+            file_lines.ranges_of(&root.join("a.move")),
+            &[
+                LineRange { lo: 3, hi: 3 },
+                LineRange { lo: 9, hi: 10 },
+                LineRange { lo: 19, hi: 21 },
+            ]
+        );
+    }
+
+    #[test]
+    fn format_only_lines_in_ranges() {
+        let original = "a\n  b\n  c\nd\n  e\n";
+        let formatted = "a\n    b\n    c\nd\n    e\n";
+        assert_eq!(
+            format_line_ranges(original, formatted, &[LineRange { lo: 2, hi: 2 }]),
+            "a\n    b\n  c\nd\n  e\n"
+        );
+        let formatted = "a\n  b c\nd\n    e\n";
+        assert_eq!(
+            format_line_ranges(original, formatted, &[LineRange { lo: 2, hi: 5 }]),
+            "a\n  b c\nd\n    e\n"
+        );
+        // Line 2 is not in the range, so the joined lines 2 and 3 are kept.
+        assert_eq!(
+            format_line_ranges(original, formatted, &[LineRange { lo: 3, hi: 5 }]),
+            "a\n  b\n  c\nd\n    e\n"
+        );
+        // The tokens moved across lines, so they can't be taken line by line.
+        let original = "foo(a,\n    b, c);\n";
+        let formatted = "foo(a, b,\n    c);\n";
+        assert_eq!(
+            format_line_ranges(original, formatted, &[LineRange { lo: 1, hi: 1 }]),
+            original
+        );
+        assert_eq!(
+            format_line_ranges(original, formatted, &[LineRange { lo: 1, hi: 2 }]),
+            formatted
+        );
+    }
+}
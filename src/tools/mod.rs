@@ -1,3 +1,8 @@
+pub mod cache;
+pub mod file_lines;
 pub mod movefmt_diff;
+pub mod package;
+pub mod report;
+pub mod source_files;
 pub mod syntax;
 pub mod utils;
@@ -8,6 +8,15 @@ use std::path::{Path, PathBuf};
 
 pub const DIFF_CONTEXT_SIZE: usize = 3;
 
+/// Returns the number of context lines around each hunk of a patch, which
+/// the `DIFF_CONTEXT_SIZE` environment variable may override.
+pub fn diff_context_size() -> usize {
+    std::env::var("DIFF_CONTEXT_SIZE")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(DIFF_CONTEXT_SIZE)
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum DiffLine {
     Context(String),
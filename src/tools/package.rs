@@ -0,0 +1,144 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tools::source_files::walk_move_files;
+use crate::tools::utils::PROJECT_FILE_NAME;
+use anyhow::Result;
+use move_package::source_package::{
+    layout::SourcePackageLayout, manifest_parser::parse_move_manifest_from_file,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The directories of a package that hold Move source files.
+const PACKAGE_SOURCE_DIRS: [SourcePackageLayout; 4] = [
+    SourcePackageLayout::Sources,
+    SourcePackageLayout::Tests,
+    SourcePackageLayout::Scripts,
+    SourcePackageLayout::Examples,
+];
+
+/// Returns `true` if `dir` is the root of a Move package.
+pub fn is_package_root(dir: &Path) -> bool {
+    dir.join(PROJECT_FILE_NAME).is_file()
+}
+
+/// Returns the root of the Move package that `dir` belongs to, i.e. the nearest
+/// of `dir` and its parents with a Move.toml.
+pub fn find_package_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| is_package_root(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Collects the `.move` files of the package rooted at `root`.
+///
+/// Only the source directories of the package are walked, so `build/` is
+/// never formatted. With `include_deps`, the local (path) dependencies of the
+/// package are collected too, recursively. Git dependencies are always skipped.
+pub fn collect_package_files(root: &Path, include_deps: bool) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut visited = HashSet::new();
+    collect_package_files_internal(root, include_deps, &mut visited, &mut files)?;
+    Ok(files)
+}
+
+fn collect_package_files_internal(
+    root: &Path,
+    include_deps: bool,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let root = root.canonicalize()?;
+    if !visited.insert(root.clone()) {
+        return Ok(());
+    }
+    let manifest = parse_move_manifest_from_file(&root)?;
+
+    for layout in PACKAGE_SOURCE_DIRS.iter() {
+        let dir = root.join(layout.path());
+        if dir.is_dir() {
+            files.extend(walk_move_files(&dir));
+        }
+    }
+
+    if include_deps {
+        for dep in manifest
+            .dependencies
+            .values()
+            .chain(manifest.dev_dependencies.values())
+        {
+            // Git and custom dependencies are fetched copies, not code of this repository.
+            if dep.git_info.is_some() || dep.node_info.is_some() {
+                continue;
+            }
+            collect_package_files_internal(&root.join(&dep.local), include_deps, visited, files)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn collect_sources_of_package_and_local_deps() {
+        let root = env::temp_dir().join(format!("movefmt-package-{}", std::process::id()));
+        let files = [
+            "app/sources/a.move",
+            "app/tests/t.move",
+            "app/scripts/s.move",
+            "app/examples/e.move",
+            "app/build/App/sources/b.move",
+            "app/other/o.move",
+            "lib/sources/l.move",
+        ];
+        for file in files {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, "").unwrap();
+        }
+        fs::write(
+            root.join("app/Move.toml"),
+            r#"
+                [package]
+                name = "App"
+                version = "0.0.1"
+
+                [dependencies]
+                Lib = { local = "../lib" }
+                Remote = { git = "https://github.com/movebit/remote.git", rev = "main" }
+            "#,
+        )
+        .unwrap();
+        // A cycle back to `app`.
+        fs::write(
+            root.join("lib/Move.toml"),
+            r#"
+                [package]
+                name = "Lib"
+                version = "0.0.1"
+
+                [dependencies]
+                App = { local = "../app" }
+            "#,
+        )
+        .unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let package_files = collect_package_files(&root.join("app"), false);
+        let all_files = collect_package_files(&root.join("app"), true);
+        let package_root = find_package_root(&root.join("app/sources"));
+        fs::remove_dir_all(&root).unwrap();
+
+        let expected: Vec<_> = files[..4].iter().map(|file| root.join(file)).collect();
+        assert_eq!(package_files.unwrap(), expected);
+        let mut expected = expected;
+        expected.push(root.join("lib/sources/l.move"));
+        assert_eq!(all_files.unwrap(), expected);
+        assert_eq!(package_root, Some(root.join("app")));
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tools::movefmt_diff::{make_diff, ModifiedLines};
+use crate::tools::utils::Timer;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+/// How many of the slowest files a report lists.
+const SLOWEST_FILES_COUNT: usize = 10;
+
+/// Format of the `--report` summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("Invalid value for `--report`: `{}`", s)),
+        }
+    }
+}
+
+/// Time spent on a single file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTiming {
+    pub file: String,
+    pub parse_secs: f32,
+    pub format_secs: f32,
+}
+
+impl FileTiming {
+    fn total_secs(&self) -> f32 {
+        self.parse_secs + self.format_secs
+    }
+}
+
+/// Summary of a run over many files.
+///
+/// Every file given to movefmt is either changed, unchanged, ignored or skipped.
+#[derive(Debug, Default, Serialize)]
+pub struct RunReport {
+    /// Files given to movefmt, including the ignored ones.
+    pub files: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    /// Files matched by the `ignore` option.
+    pub ignored: usize,
+    /// Files that could not be read or parsed, or whose config is invalid.
+    pub skipped: usize,
+    pub lines_changed: usize,
+    pub total_secs: f32,
+    pub slowest_files: Vec<FileTiming>,
+}
+
+impl RunReport {
+    /// Records a formatted file.
+    pub fn add_formatted_file(
+        &mut self,
+        file: &Path,
+        original_text: &str,
+        formatted_text: &str,
+        timer: &Timer,
+    ) {
+        if original_text == formatted_text {
+            self.unchanged += 1;
+        } else {
+            self.changed += 1;
+            let modified_lines = ModifiedLines::from(make_diff(original_text, formatted_text, 0));
+            self.lines_changed += modified_lines
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.lines_removed as usize).max(chunk.lines.len()))
+                .sum::<usize>();
+        }
+        self.slowest_files.push(FileTiming {
+            file: file.display().to_string(),
+            parse_secs: timer.get_parse_time(),
+            format_secs: timer.get_format_time(),
+        });
+    }
+
+    /// Records the total time, and keeps only the slowest files.
+    pub fn finish(&mut self, total: Duration) {
+        self.total_secs = Timer::duration_to_f32(total);
+        self.slowest_files
+            .sort_by(|a, b| b.total_secs().total_cmp(&a.total_secs()));
+        self.slowest_files.truncate(SLOWEST_FILES_COUNT);
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String, serde_json::Error> {
+        match format {
+            ReportFormat::Text => Ok(self.to_string()),
+            ReportFormat::Json => serde_json::to_string_pretty(self),
+        }
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} files: {} changed, {} unchanged, {} ignored, {} skipped because of read, parse or config errors",
+            self.files, self.changed, self.unchanged, self.ignored, self.skipped
+        )?;
+        writeln!(f, "{} lines changed", self.lines_changed)?;
+        writeln!(f, "Total time: {:.3} secs", self.total_secs)?;
+        if !self.slowest_files.is_empty() {
+            writeln!(f, "Slowest files:")?;
+        }
+        for timing in &self.slowest_files {
+            writeln!(
+                f,
+                "  {:.3} secs (parse {:.3}, format {:.3})  {}",
+                timing.total_secs(),
+                timing.parse_secs,
+                timing.format_secs,
+                timing.file
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Instant;
+
+    fn timer(parse_millis: u64, format_millis: u64) -> Timer {
+        let start = Instant::now();
+        let parsed = start + Duration::from_millis(parse_millis);
+        Timer::DoneFormatting(start, parsed, parsed + Duration::from_millis(format_millis))
+    }
+
+    #[test]
+    fn count_changed_lines() {
+        let mut report = RunReport::default();
+        report.add_formatted_file(Path::new("a.move"), "a\n", "a\n", &timer(1, 1));
+        // A replaced line, a removed line and two added lines.
+        report.add_formatted_file(
+            Path::new("b.move"),
+            "a\nb\nc\nd\ne\n",
+            "a\nB\nc\ne\nf\ng\n",
+            &timer(1, 1),
+        );
+        assert_eq!((report.changed, report.unchanged), (1, 1));
+        assert_eq!(report.lines_changed, 4);
+    }
+
+    #[test]
+    fn keep_slowest_files() {
+        let mut report = RunReport::default();
+        for i in 0..SLOWEST_FILES_COUNT as u64 + 2 {
+            let file = format!("{}.move", i);
+            report.add_formatted_file(Path::new(&file), "a\n", "a\n", &timer(i, 10 * i));
+        }
+        report.finish(Duration::from_secs(1));
+        assert_eq!(report.slowest_files.len(), SLOWEST_FILES_COUNT);
+        assert_eq!(report.slowest_files[0].file, "11.move");
+        assert_eq!(report.slowest_files[SLOWEST_FILES_COUNT - 1].file, "2.move");
+        assert_eq!(report.total_secs, 1.0);
+    }
+
+    fn sample_report() -> RunReport {
+        RunReport {
+            files: 5,
+            changed: 1,
+            unchanged: 2,
+            ignored: 1,
+            skipped: 1,
+            lines_changed: 3,
+            total_secs: 0.5,
+            slowest_files: vec![FileTiming {
+                file: "a.move".to_string(),
+                parse_secs: 0.125,
+                format_secs: 0.25,
+            }],
+        }
+    }
+
+    #[test]
+    fn render_text() {
+        assert_eq!(
+            sample_report().render(ReportFormat::Text).unwrap(),
+            "5 files: 1 changed, 2 unchanged, 1 ignored, 1 skipped because of read, parse or config errors\n\
+             3 lines changed\n\
+             Total time: 0.500 secs\n\
+             Slowest files:\n  \
+             0.375 secs (parse 0.125, format 0.250)  a.move\n"
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let json = sample_report().render(ReportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "files": 5,
+                "changed": 1,
+                "unchanged": 2,
+                "ignored": 1,
+                "skipped": 1,
+                "lines_changed": 3,
+                "total_secs": 0.5,
+                "slowest_files": [{"file": "a.move", "parse_secs": 0.125, "format_secs": 0.25}],
+            })
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// Copyright (c) The BitsLab.MoveBit Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use commentfmt::config::IgnoreList;
+use commentfmt::ignore_path::IgnorePathSet;
+use commentfmt::ErrorKind;
+use ignore::WalkBuilder;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the file listing gitignore-style patterns of files movefmt should skip.
+pub const MOVEFMT_IGNORE_FILE_NAME: &str = ".movefmtignore";
+
+/// Decides which `.move` files found by walking directories or expanding
+/// glob arguments get formatted.
+pub struct SourceFileFilter {
+    include: IgnorePathSet,
+    exclude: IgnorePathSet,
+}
+
+impl SourceFileFilter {
+    /// Builds a filter from `--include`/`--exclude` globs, relative to `root`.
+    pub fn new(root: &Path, include: &[String], exclude: &[String]) -> Result<Self, ErrorKind> {
+        let to_set = |globs: &[String]| {
+            let list = IgnoreList::from_paths(root, globs.iter().map(PathBuf::from));
+            IgnorePathSet::from_ignore_list(&list).map_err(ErrorKind::InvalidGlobPattern)
+        };
+        Ok(SourceFileFilter {
+            include: to_set(include)?,
+            exclude: to_set(exclude)?,
+        })
+    }
+
+    pub fn is_selected(&self, path: &Path) -> bool {
+        (self.include.is_empty() || self.include.is_match(path)) && !self.exclude.is_match(path)
+    }
+}
+
+/// Expands the command line arguments into the list of files to format.
+///
+/// Directories are walked recursively and glob patterns are expanded, both
+/// honoring `.gitignore` and `.movefmtignore` files and the `filter`. Files
+/// given explicitly are always kept, as are paths that don't exist so the
+/// caller can report them.
+pub fn collect_source_files(
+    args: &[String],
+    filter: &SourceFileFilter,
+) -> Result<Vec<PathBuf>, ErrorKind> {
+    let mut files = vec![];
+    for arg in args {
+        let path = PathBuf::from(arg);
+        if path.is_dir() {
+            files.extend(walk_move_files(&path).filter(|p| filter.is_selected(p)));
+        } else if !path.exists() && is_glob(arg) {
+            let (base, pattern) = split_glob(&path);
+            // Anchor the pattern to `base`, like a shell glob would be.
+            let glob = IgnoreList::from_paths(&base, [PathBuf::from(format!("/{}", pattern))]);
+            let glob =
+                IgnorePathSet::from_ignore_list(&glob).map_err(ErrorKind::InvalidGlobPattern)?;
+            files.extend(
+                walk_move_files(&base).filter(|p| glob.is_match(p) && filter.is_selected(p)),
+            );
+        } else {
+            files.push(path);
+        }
+    }
+
+    // we will do comparison later, so here tries to canonicalize first
+    // to get the expected behavior.
+    let mut seen = HashSet::new();
+    Ok(files
+        .into_iter()
+        .map(|p| p.canonicalize().unwrap_or(p))
+        .filter(|p| seen.insert(p.clone()))
+        .collect())
+}
+
+/// Walks `dir` in a stable order, yielding every `.move` file not ignored by
+/// a `.gitignore` or `.movefmtignore` file. Formatted copies, named
+/// `*.fmt.move`, are skipped too.
+pub fn walk_move_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkBuilder::new(dir)
+        .require_git(false)
+        .add_custom_ignore_filename(MOVEFMT_IGNORE_FILE_NAME)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("skip walking entry: {}", e);
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().map_or(false, |t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "move"))
+        .filter(|p| !is_formatted_copy(p))
+}
+
+/// Returns `true` for `*.fmt.move` files, such as the expected outputs of the
+/// tests of movefmt.
+fn is_formatted_copy(path: &Path) -> bool {
+    path.file_stem()
+        .map_or(false, |stem| stem.to_string_lossy().ends_with(".fmt"))
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '[', '{'])
+}
+
+/// Splits a glob into the directory to walk and the pattern relative to it,
+/// e.g. `sources/**/*.move` into `sources` and `**/*.move`.
+fn split_glob(path: &Path) -> (PathBuf, String) {
+    let mut base = PathBuf::new();
+    let mut components = path.components().peekable();
+    while let Some(c) = components.peek() {
+        match c {
+            Component::Normal(s) if is_glob(&s.to_string_lossy()) => break,
+            _ => base.push(c),
+        }
+        components.next();
+    }
+    let pattern = components
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    if base.as_os_str().is_empty() {
+        base.push(".");
+    }
+    (base, pattern)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_glob_at_first_pattern_component() {
+        assert_eq!(
+            split_glob(Path::new("sources/**/*.move")),
+            (PathBuf::from("sources"), "**/*.move".to_string())
+        );
+        assert_eq!(
+            split_glob(Path::new("*.move")),
+            (PathBuf::from("."), "*.move".to_string())
+        );
+    }
+
+    #[test]
+    fn walk_skips_formatted_copies() {
+        let dir = std::env::temp_dir().join(format!("movefmt-walk-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sources")).unwrap();
+        for name in [
+            "sources/a.move",
+            "sources/a.fmt.move",
+            "sources/b.txt",
+            "c.move",
+        ] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let files: Vec<_> = walk_move_files(&dir).collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(files, [dir.join("c.move"), dir.join("sources/a.move")]);
+    }
+}
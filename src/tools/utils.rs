@@ -7,6 +7,9 @@ use move_command_line_common::files::FileHash;
 use move_ir_types::location::*;
 
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::{path::*, vec};
 
@@ -64,7 +67,8 @@ impl FileLineMapping {
     pub fn update(&mut self, filepath: PathBuf, content: &str) {
         let mut v = vec![0];
         for (index, s) in content.as_bytes().iter().enumerate() {
-            // TODO how to support windows \r\n
+            // A `\r\n` line ending ends with `\n` too, and `\r` is never part
+            // of a token, so lines and columns come out right for both.
             if *s == 10 {
                 // \n
                 v.push((index + 1) as ByteIndex);
@@ -171,6 +175,85 @@ abc        "#,
             }
         );
     }
+
+    #[test]
+    fn parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..100).collect();
+        let squares = parallel_map(items.clone(), 4, |x| x * x);
+        assert_eq!(squares, items.iter().map(|x| x * x).collect::<Vec<_>>());
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("movefmt-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        let mut entries: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn replace_file_keeps_backup() {
+        let dir = temp_dir("replace-backup");
+        let file = dir.join("a.move");
+        fs::write(&file, "old\n").unwrap();
+        replace_file(&file, "old\n", "new\n", true).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new\n");
+        assert_eq!(fs::read_to_string(dir.join("a.move.bak")).unwrap(), "old\n");
+        // The temporary file was renamed over `file`.
+        assert_eq!(dir_entries(&dir), ["a.move", "a.move.bak"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replace_file_refuses_changed_file() {
+        let dir = temp_dir("replace-changed");
+        let file = dir.join("a.move");
+        fs::write(&file, "edited\n").unwrap();
+        assert!(replace_file(&file, "old\n", "new\n", true).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "edited\n");
+        assert_eq!(dir_entries(&dir), ["a.move"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replace_file_keeps_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_dir("replace-mode");
+        let file = dir.join("a.move");
+        fs::write(&file, "old\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        replace_file(&file, "old\n", "new\n", false).unwrap();
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replace_file_writes_through_symlink() {
+        let dir = temp_dir("replace-symlink");
+        let target = dir.join("target.move");
+        let link = dir.join("link.move");
+        fs::write(&target, "old\n").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        replace_file(&link, "old\n", "new\n", false).unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new\n");
+        assert_eq!(dir_entries(&dir), ["link.move", "target.move"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -352,11 +435,57 @@ impl Timer {
         }
     }
 
+    /// Returns the time spent in each phase, as printed in verbose mode.
+    pub fn phase_times(&self) -> String {
+        format!(
+            "Spent {0:.3} secs in the parsing phase, and {1:.3} secs in the formatting phase",
+            self.get_parse_time(),
+            self.get_format_time(),
+        )
+    }
+
     pub fn duration_to_f32(d: Duration) -> f32 {
         d.as_secs() as f32 + d.subsec_nanos() as f32 / 1_000_000_000f32
     }
 }
 
+/// Maps `f` over `items` on up to `jobs` threads.
+///
+/// The results are returned in the order of `items`, whatever order the
+/// threads finish them in.
+pub fn parallel_map<T, R, F>(items: Vec<T>, jobs: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(vec![]);
+    std::thread::scope(|s| {
+        for _ in 0..jobs {
+            s.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                match next {
+                    Some((index, item)) => {
+                        let result = f(item);
+                        results.lock().unwrap().push((index, result));
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 pub fn mk_result_filepath(x: &Path) -> PathBuf {
     let mut x = x.to_path_buf();
     let b = x
@@ -372,9 +501,55 @@ pub fn mk_result_filepath(x: &Path) -> PathBuf {
     ret
 }
 
+/// Replaces the content of `file`, which was `original` when it was read,
+/// with `content`.
+///
+/// The content is written to a temporary file in the same directory, which
+/// then replaces `file` by a rename, so an interrupted write never leaves a
+/// truncated file behind. The permissions of `file` are kept. With `backup`,
+/// the original content is kept in `<file>.bak`. Fails without writing
+/// anything if `file` no longer contains `original`.
+pub fn replace_file(file: &Path, original: &str, content: &str, backup: bool) -> io::Result<()> {
+    // Write through symbolic links instead of replacing them.
+    let file = file.canonicalize()?;
+    let permissions = fs::metadata(&file)?.permissions();
+    if fs::read(&file)? != original.as_bytes() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the file changed on disk since it was read",
+        ));
+    }
+    if backup {
+        let mut backup_file = file.clone().into_os_string();
+        backup_file.push(".bak");
+        fs::copy(&file, backup_file)?;
+    }
+
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file.file_name().unwrap_or_default());
+    tmp_name.push(format!(".{}.movefmt.tmp", std::process::id()));
+    let tmp_file = file.with_file_name(tmp_name);
+    let result =
+        write_new_file(&tmp_file, content, permissions).and_then(|_| fs::rename(&tmp_file, &file));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_file);
+    }
+    result
+}
+
+fn write_new_file(file: &Path, content: &str, permissions: fs::Permissions) -> io::Result<()> {
+    let mut f = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file)?;
+    f.write_all(content.as_bytes())?;
+    f.sync_all()?;
+    fs::set_permissions(file, permissions)
+}
+
 pub const PROJECT_FILE_NAME: &str = "Move.toml";
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(feature = "pprof", not(target_os = "windows")))]
 pub fn cpu_pprof(_seconds: u64) {
     use std::fs::File;
     use std::str::FromStr;
@@ -401,7 +576,51 @@ pub fn cpu_pprof(_seconds: u64) {
         };
     });
 }
-#[cfg(target_os = "windows")]
+#[cfg(not(all(feature = "pprof", not(target_os = "windows"))))]
 pub fn cpu_pprof(_seconds: u64) {
-    tracing::error!("Can't run pprof in Windows");
+    tracing::error!("Can't run pprof in Windows or without the `pprof` feature");
+}
+
+/// Samples the CPU of every thread from `start` until `finish`, which writes
+/// a flamegraph if the output file ends with `.svg`, or a pprof protobuf
+/// profile if it ends with `.pb`.
+#[cfg(all(feature = "pprof", not(target_os = "windows")))]
+pub struct CpuProfiler {
+    guard: pprof::ProfilerGuard<'static>,
+    output: PathBuf,
+}
+
+#[cfg(all(feature = "pprof", not(target_os = "windows")))]
+impl CpuProfiler {
+    pub fn start(output: PathBuf) -> anyhow::Result<CpuProfiler> {
+        if !matches!(
+            output.extension().and_then(|ext| ext.to_str()),
+            Some("svg" | "pb")
+        ) {
+            anyhow::bail!(
+                "the profile output `{}` must end with `.svg` or `.pb`",
+                output.display()
+            );
+        }
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(1000)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()?;
+        Ok(CpuProfiler { guard, output })
+    }
+
+    pub fn finish(self) -> anyhow::Result<()> {
+        use pprof::protos::Message;
+        let report = self.guard.report().build()?;
+        let mut file = fs::File::create(&self.output)?;
+        if self.output.extension().map_or(false, |ext| ext == "svg") {
+            report.flamegraph(file)?;
+        } else {
+            let mut content = Vec::new();
+            report.pprof()?.write_to_vec(&mut content)?;
+            file.write_all(&content)?;
+        }
+        tracing::debug!("pprof file at {:?}", self.output);
+        Ok(())
+    }
 }
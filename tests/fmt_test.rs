@@ -278,3 +278,58 @@ fn regression_test_main() {
     }
     eprintln!("formated {} files", num);
 }
+
+#[test]
+fn test_crlf_input() {
+    let content_lf = std::fs::read_to_string("./tests/complex/input1.move").unwrap();
+    let content_crlf = content_lf.replace('\n', "\r\n");
+
+    let format_lf =
+        movefmt::core::fmt::format_entry(&content_lf, commentfmt::Config::default()).unwrap();
+    let format_crlf =
+        movefmt::core::fmt::format_entry(&content_crlf, commentfmt::Config::default()).unwrap();
+    // `newline_style = "Auto"` keeps the line endings of the input.
+    assert_eq!(format_crlf, format_lf.replace('\n', "\r\n"));
+}
+
+#[test]
+fn test_hard_tabs() {
+    let content = std::fs::read_to_string("./tests/aptos_framework_case/MultiToken.move").unwrap();
+    let format = |max_width: usize, hard_tabs: bool| {
+        let mut config = commentfmt::Config::default();
+        config.set().max_width(max_width);
+        config.set().hard_tabs(hard_tabs);
+        movefmt::core::fmt::format_entry(&content, config).unwrap()
+    };
+
+    // Narrow the width to the widest formatted line, so some lines are exactly
+    // `max_width` wide. A tab is `tab_spaces` columns, so they must not wrap
+    // differently with tabs.
+    let max_width = format(90, false)
+        .lines()
+        .map(|line| line.chars().count())
+        .filter(|width| *width <= 90)
+        .max()
+        .unwrap();
+    let format_spaces = format(max_width, false);
+    let format_tabs = format(max_width, true);
+
+    let tab_spaces = commentfmt::Config::default().tab_spaces();
+    let expected: String = format_spaces
+        .split_inclusive('\n')
+        .map(|line| {
+            let content = line.trim_start_matches(' ');
+            let indent = line.len() - content.len();
+            if indent == 0 {
+                return line.to_string();
+            }
+            format!(
+                "{}{}{}",
+                "\t".repeat(indent / tab_spaces),
+                " ".repeat(indent % tab_spaces),
+                content
+            )
+        })
+        .collect();
+    assert_eq!(format_tabs, expected);
+}